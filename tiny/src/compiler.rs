@@ -1,11 +1,20 @@
-use crate::value_object::ast::{BinaryOperation, Expr, Stmt};
-use crate::value_object::opcode::OpCode;
-use crate::value_object::tiny_object::TinyObject;
+use crate::value_object::ast::{
+    BinaryOperation, Expr, Pattern, Stmt, TypeExpr, UnaryOperation, fn_group, prelude,
+};
+use crate::value_object::opcode::{Capture, OpCode};
+use crate::value_object::tiny_object::{Function, TinyObject};
+use std::collections::HashMap;
+use std::rc::Rc;
 
 #[derive(Debug)]
 pub enum CompileError {
     #[allow(dead_code)]
     UnsupportedExpr,
+    UndefinedVariable(String),
+    UnresolvedField(String),
+    UndefinedConstructor(String),
+    AssignToCapturedVariable(String),
+    BreakOutsideLoop,
 }
 
 pub fn compile_error_to_message(e: CompileError) -> String {
@@ -13,26 +22,224 @@ pub fn compile_error_to_message(e: CompileError) -> String {
         CompileError::UnsupportedExpr => {
             "unsupported expression encountered during compilation".to_string()
         }
+        CompileError::UndefinedVariable(name) => format!("variable is not defined: {}", name),
+        CompileError::AssignToCapturedVariable(name) => {
+            format!(
+                "can not assign to a variable captured by a closure: {}",
+                name
+            )
+        }
+        CompileError::BreakOutsideLoop => "break or continue outside of a loop".to_string(),
+        CompileError::UndefinedConstructor(name) => {
+            format!("constructor is not defined: {}", name)
+        }
+        CompileError::UnresolvedField(name) => {
+            format!("offset of the field is not resolved: {}", name)
+        }
     }
 }
 
+// bindings of one function body; the top-level program is the outermost scope
+#[derive(Default)]
+struct Scope {
+    // slot index of a local is its position; inner scopes are pushed to the back
+    locals: Vec<String>,
+    // variables captured from enclosing scopes, in the order of the closure's upvalues
+    upvalues: Vec<(String, Capture)>,
+}
+
 pub(crate) struct Compiler {
     code: Vec<OpCode>,
+    scopes: Vec<Scope>,
+    loops: Vec<Loop>,
+    // slot index of a global is its position; top-level bindings and functions live here
+    globals: Vec<String>,
+    // tag of each constructor of the declared sum types
+    constructors: HashMap<String, usize>,
+}
+
+// jumps of the innermost loop being compiled
+struct Loop {
+    start: usize,
+    // positions of `Break` opcodes to patch with the end of the loop
+    breaks: Vec<usize>,
+    // `try` bodies entered inside the loop, whose handlers a jump out of them must remove
+    tries: usize,
 }
 
 impl Compiler {
     pub fn new() -> Self {
-        Self { code: Vec::new() }
+        Self {
+            code: Vec::new(),
+            scopes: vec![Scope::default()],
+            loops: Vec::new(),
+            globals: Vec::new(),
+            constructors: HashMap::new(),
+        }
     }
 
-    pub fn compile_stmt(&mut self, stmt: Stmt) -> Result<Vec<OpCode>, CompileError> {
+    pub fn compile(&mut self, program: Vec<Stmt>) -> Result<Vec<OpCode>, CompileError> {
+        for stmt in prelude() {
+            self.compile_stmt(stmt, false)?;
+        }
+        let last: usize = program.len().saturating_sub(1);
+        let groups: Vec<Vec<String>> = (0..program.len()).map(|i| fn_group(&program, i)).collect();
+        for ((i, stmt), group) in program.into_iter().enumerate().zip(groups) {
+            self.globals.extend(group);
+            self.compile_stmt(stmt, i == last)?;
+        }
+        Ok(self.code.clone())
+    }
+
+    // only the last statement leaves its value on the stack as the program's result
+    fn compile_stmt(&mut self, stmt: Stmt, is_last: bool) -> Result<(), CompileError> {
         match stmt {
             Stmt::Expr(expr) => {
                 self.compile_expr(expr)?;
-                // if you use file read mode, you must pop!!
+                if !is_last {
+                    self.code.push(OpCode::Pop);
+                }
+            }
+            Stmt::Let { name, value } | Stmt::LetMut { name, value } => {
+                self.compile_expr(value)?;
+                // registered after the value so that `let x = x + 1` sees the previous `x`
+                let global: usize = self.globals.len();
+                self.globals.push(name);
+                self.code.push(OpCode::StoreGlobal(global));
+            }
+            Stmt::LetTuple { names, value } => {
+                let slot: usize = self.compile_tuple(value)?;
+                for (i, name) in names.into_iter().enumerate() {
+                    self.code.push(OpCode::LoadLocal(slot));
+                    self.code.push(OpCode::TupleField(i));
+                    let global: usize = self.globals.len();
+                    self.globals.push(name);
+                    self.code.push(OpCode::StoreGlobal(global));
+                }
+                self.locals().pop();
+            }
+            Stmt::Fn {
+                name, params, body, ..
+            } => self.compile_fn(name, params, body)?,
+            Stmt::Type { variants, .. } => self.compile_type(variants),
+        }
+        Ok(())
+    }
+
+    fn compile_fn(
+        &mut self,
+        name: String,
+        params: Vec<String>,
+        body: Expr,
+    ) -> Result<(), CompileError> {
+        // registered with its group before any body so that the functions can call each other
+        let global: usize = self
+            .globals
+            .iter()
+            .rposition(|n| *n == name)
+            .ok_or_else(|| CompileError::UndefinedVariable(name.clone()))?;
+
+        self.compile_closure(name, params, body)?;
+        self.code.push(OpCode::StoreGlobal(global));
+        Ok(())
+    }
+
+    // every constructor becomes a global: a value without fields, otherwise a function
+    fn compile_type(&mut self, variants: Vec<(String, Vec<TypeExpr>)>) {
+        for (tag, (name, fields)) in variants.into_iter().enumerate() {
+            self.constructors.insert(name.clone(), tag);
+            let global: usize = self.globals.len();
+            self.globals.push(name.clone());
+
+            let arity: usize = fields.len();
+            if arity == 0 {
+                self.code.push(OpCode::MakeVariant {
+                    name: Rc::from(name),
+                    tag,
+                    arity,
+                });
+            } else {
+                self.compile_constructor(name, tag, arity);
+            }
+            self.code.push(OpCode::StoreGlobal(global));
+        }
+    }
+
+    fn compile_constructor(&mut self, name: String, tag: usize, arity: usize) {
+        let jump_pos: usize = self.code.len();
+        self.code.push(OpCode::Jump(0)); // [Jump(0)]
+        let entry: usize = self.code.len();
+        for slot in 0..arity {
+            self.code.push(OpCode::LoadLocal(slot));
+        }
+        self.code.push(OpCode::MakeVariant {
+            name: Rc::from(name.as_str()),
+            tag,
+            arity,
+        });
+        self.code.push(OpCode::Return); // [Jump(0), LoadLocal.., MakeVariant, Return]
+        let end: usize = self.code.len();
+        self.code[jump_pos] = OpCode::Jump(end);
+
+        self.code.push(OpCode::MakeClosure {
+            function: Rc::new(Function { name, entry, arity }),
+            captures: Vec::new(),
+        });
+    }
+
+    fn compile_closure(
+        &mut self,
+        name: String,
+        params: Vec<String>,
+        body: Expr,
+    ) -> Result<(), CompileError> {
+        let jump_pos: usize = self.code.len();
+        self.code.push(OpCode::Jump(0)); // [Jump(0)]
+        let entry: usize = self.code.len();
+        let arity: usize = params.len();
+        self.scopes.push(Scope {
+            locals: params,
+            upvalues: Vec::new(),
+        });
+        let body = self.compile_expr(body);
+        let scope: Scope = self.scopes.pop().unwrap_or_default();
+        body?;
+        self.code.push(OpCode::Return); // [Jump(0), body_cmds, Return]
+        let end: usize = self.code.len();
+        self.mark_tail_calls(entry, end);
+        self.code[jump_pos] = OpCode::Jump(end); // [Jump(end), body_cmds, Return]
+
+        self.code.push(OpCode::MakeClosure {
+            function: Rc::new(Function { name, entry, arity }),
+            captures: scope
+                .upvalues
+                .into_iter()
+                .map(|(_, capture)| capture)
+                .collect(),
+        }); // [Jump(end), body_cmds, Return, MakeClosure]
+        Ok(())
+    }
+
+    // a call whose result is returned right away does not need a frame of its own
+    fn mark_tail_calls(&mut self, entry: usize, end: usize) {
+        for pos in entry..end {
+            if let OpCode::Call(argc) = self.code[pos]
+                && self.returns_from(pos + 1)
+            {
+                self.code[pos] = OpCode::TailCall(argc);
+            }
+        }
+    }
+
+    // whether running from `pos` reaches a Return without doing anything but jumping
+    fn returns_from(&self, mut pos: usize) -> bool {
+        loop {
+            match self.code.get(pos) {
+                Some(OpCode::Return) => return true,
+                Some(OpCode::Jump(target)) if *target > pos => pos = *target,
+                _ => return false,
             }
         }
-        Ok(self.code.clone())
     }
 
     fn compile_expr(&mut self, expr: Expr) -> Result<(), CompileError> {
@@ -49,20 +256,518 @@ impl Compiler {
                 self.code.push(OpCode::Push(TinyObject::Float(f)));
                 Ok(())
             }
+            Expr::Decimal(d) => {
+                self.code.push(OpCode::Push(TinyObject::Decimal(d)));
+                Ok(())
+            }
+            Expr::String(s) => {
+                self.code
+                    .push(OpCode::Push(TinyObject::String(Rc::from(s))));
+                Ok(())
+            }
             Expr::BinOp(boxed_op) => self.compile_binop(*boxed_op),
+            Expr::UnaryOp(boxed_op) => self.compile_unaryop(*boxed_op),
             Expr::If { cond, thn, els } => self.compile_if(*cond, *thn, *els),
+            // the fallback is only evaluated for `None`
+            Expr::Default { option, fallback } => self.compile_match(
+                *option,
+                vec![
+                    (
+                        Pattern::Constructor {
+                            name: String::from("Some"),
+                            args: vec![Pattern::Var(String::from("$some"))],
+                        },
+                        Expr::Var(String::from("$some")),
+                    ),
+                    (Pattern::Wildcard, *fallback),
+                ],
+            ),
+            Expr::Let { name, value, body } | Expr::LetMut { name, value, body } => {
+                self.compile_let(name, *value, *body)
+            }
+            Expr::Assign { name, value } => {
+                self.compile_expr(*value)?;
+                self.compile_assign(name)?;
+                self.code.push(OpCode::MakeTuple(0));
+                Ok(())
+            }
+            Expr::Block(items) => {
+                let last: usize = items.len().saturating_sub(1);
+                for (i, item) in items.into_iter().enumerate() {
+                    self.compile_expr(item)?;
+                    if i != last {
+                        self.code.push(OpCode::Pop);
+                    }
+                }
+                Ok(())
+            }
+            Expr::While { cond, body } => self.compile_while(*cond, *body),
+            Expr::Break => {
+                self.exit_tries()?;
+                let pos: usize = self.code.len();
+                self.code.push(OpCode::Break(0));
+                let current: &mut Loop = self
+                    .loops
+                    .last_mut()
+                    .ok_or(CompileError::BreakOutsideLoop)?;
+                current.breaks.push(pos);
+                Ok(())
+            }
+            Expr::Throw(value) => {
+                self.compile_expr(*value)?;
+                self.code.push(OpCode::Throw);
+                Ok(())
+            }
+            Expr::Try {
+                body,
+                name,
+                handler,
+            } => self.compile_try(*body, name, *handler),
+            Expr::Continue => {
+                self.exit_tries()?;
+                let current: &Loop = self.loops.last().ok_or(CompileError::BreakOutsideLoop)?;
+                self.code.push(OpCode::Continue(current.start));
+                Ok(())
+            }
+            Expr::LetTuple { names, value, body } => {
+                let slot: usize = self.compile_tuple(*value)?;
+                for (i, name) in names.into_iter().enumerate() {
+                    self.code.push(OpCode::LoadLocal(slot));
+                    self.code.push(OpCode::TupleField(i));
+                    let binding: usize = self.locals().len();
+                    self.code.push(OpCode::StoreLocal(binding));
+                    self.locals().push(name);
+                }
+                let body = self.compile_expr(*body);
+                self.locals().truncate(slot);
+                body
+            }
+            Expr::Tuple(elements) => {
+                let n: usize = elements.len();
+                for e in elements {
+                    self.compile_expr(e)?;
+                }
+                self.code.push(OpCode::MakeTuple(n));
+                Ok(())
+            }
+            Expr::Var(name) => self.compile_var(name),
+            // the typechecker turns an Int that stands for a Float into a conversion
+            Expr::Annotated { expr, .. } => self.compile_expr(*expr),
+            Expr::Convert { expr, target } => {
+                self.compile_expr(*expr)?;
+                // the typechecker only accepts Int, Float and Decimal as the target
+                if let TypeExpr::Named { name, .. } = target {
+                    match name.as_str() {
+                        "Int" => self.code.push(OpCode::ToInt),
+                        "Float" => self.code.push(OpCode::ToFloat),
+                        _ => self.code.push(OpCode::ToDecimal),
+                    }
+                }
+                Ok(())
+            }
+            Expr::Lambda { params, body, .. } => {
+                self.compile_closure(String::from("<lambda>"), params, *body)
+            }
+            Expr::List(elements) => {
+                let n: usize = elements.len();
+                for e in elements {
+                    self.compile_expr(e)?;
+                }
+                self.code.push(OpCode::MakeList(n));
+                Ok(())
+            }
+            Expr::Index { expr, index } => {
+                self.compile_expr(*expr)?;
+                self.compile_expr(*index)?;
+                self.code.push(OpCode::Index);
+                Ok(())
+            }
+            Expr::Slice { expr, start, end } => {
+                self.compile_expr(*expr)?;
+                self.compile_expr(*start)?;
+                self.compile_expr(*end)?;
+                self.code.push(OpCode::Slice);
+                Ok(())
+            }
+            Expr::Len(expr) => {
+                self.compile_expr(*expr)?;
+                self.code.push(OpCode::Len);
+                Ok(())
+            }
+            Expr::Match { scrutinee, arms } => self.compile_match(*scrutinee, arms),
+            Expr::Record(fields) => {
+                // the layout of a record sorts its fields by name
+                let mut names: Vec<&String> = fields.iter().map(|(name, _)| name).collect();
+                names.sort();
+                let offsets: Vec<usize> = fields
+                    .iter()
+                    .map(|(name, _)| names.binary_search(&name).unwrap_or_default())
+                    .collect();
+                for (_, value) in fields {
+                    self.compile_expr(value)?;
+                }
+                self.code.push(OpCode::MakeRecord(offsets));
+                Ok(())
+            }
+            Expr::Field {
+                expr,
+                field,
+                offset,
+            } => {
+                let offset: usize = offset.ok_or(CompileError::UnresolvedField(field))?;
+                self.compile_expr(*expr)?;
+                self.code.push(OpCode::GetField(offset));
+                Ok(())
+            }
+            Expr::RecordUpdate {
+                record,
+                fields,
+                offsets,
+            } => {
+                let offsets: Vec<usize> = offsets.ok_or_else(|| {
+                    CompileError::UnresolvedField(
+                        fields
+                            .first()
+                            .map(|(name, _)| name.clone())
+                            .unwrap_or_default(),
+                    )
+                })?;
+                self.compile_expr(*record)?;
+                for ((_, value), offset) in fields.into_iter().zip(offsets) {
+                    self.compile_expr(value)?;
+                    self.code.push(OpCode::SetField(offset));
+                }
+                Ok(())
+            }
+            Expr::Call { callee, args } => {
+                let argc: usize = args.len();
+                self.compile_expr(*callee)?;
+                for arg in args {
+                    self.compile_expr(arg)?;
+                }
+                self.code.push(OpCode::Call(argc));
+                Ok(())
+            }
+        }
+    }
+
+    fn compile_var(&mut self, name: String) -> Result<(), CompileError> {
+        let depth: usize = self.scopes.len() - 1;
+        match self.resolve_capture(depth, &name) {
+            Some(Capture::Local(slot)) => self.code.push(OpCode::LoadLocal(slot)),
+            Some(Capture::Upvalue(index)) => self.code.push(OpCode::LoadUpvalue(index)),
+            None => match self.globals.iter().rposition(|n| *n == name) {
+                Some(global) => self.code.push(OpCode::LoadGlobal(global)),
+                None => return Err(CompileError::UndefinedVariable(name)),
+            },
+        }
+        Ok(())
+    }
+
+    // only variables of the current function and globals can be assigned to,
+    // since closures hold copies of the variables they capture
+    fn compile_assign(&mut self, name: String) -> Result<(), CompileError> {
+        let depth: usize = self.scopes.len() - 1;
+        if let Some(slot) = self.scopes[depth].locals.iter().rposition(|n| *n == name) {
+            self.code.push(OpCode::StoreLocal(slot));
+            return Ok(());
+        }
+        if self.resolve_capture(depth, &name).is_some() {
+            return Err(CompileError::AssignToCapturedVariable(name));
+        }
+        match self.globals.iter().rposition(|n| *n == name) {
+            Some(global) => self.code.push(OpCode::StoreGlobal(global)),
+            None => return Err(CompileError::UndefinedVariable(name)),
+        }
+        Ok(())
+    }
+
+    // finds `name` in the scope at `depth`, capturing it from enclosing scopes when needed
+    fn resolve_capture(&mut self, depth: usize, name: &str) -> Option<Capture> {
+        let scope: &Scope = &self.scopes[depth];
+        if let Some(slot) = scope.locals.iter().rposition(|n| n == name) {
+            return Some(Capture::Local(slot));
+        }
+        if let Some(index) = scope.upvalues.iter().position(|(n, _)| n == name) {
+            return Some(Capture::Upvalue(index));
+        }
+        if depth == 0 {
+            return None;
+        }
+
+        let capture: Capture = self.resolve_capture(depth - 1, name)?;
+        let upvalues: &mut Vec<(String, Capture)> = &mut self.scopes[depth].upvalues;
+        upvalues.push((name.to_string(), capture));
+        Some(Capture::Upvalue(upvalues.len() - 1))
+    }
+
+    fn locals(&mut self) -> &mut Vec<String> {
+        &mut self
+            .scopes
+            .last_mut()
+            .expect("the top-level scope is never popped")
+            .locals
+    }
+
+    fn compile_let(&mut self, name: String, value: Expr, body: Expr) -> Result<(), CompileError> {
+        self.compile_expr(value)?;
+        let slot: usize = self.locals().len();
+        self.code.push(OpCode::StoreLocal(slot));
+        self.locals().push(name);
+        let body = self.compile_expr(body);
+        self.locals().pop();
+        body
+    }
+
+    // each arm tests its pattern and jumps to the next arm on the first failed test
+    fn compile_match(
+        &mut self,
+        scrutinee: Expr,
+        arms: Vec<(Pattern, Expr)>,
+    ) -> Result<(), CompileError> {
+        self.compile_expr(scrutinee)?;
+        let slot: usize = self.locals().len();
+        self.code.push(OpCode::StoreLocal(slot));
+        // `$` never appears in an identifier, so the scrutinee can not be shadowed
+        self.locals().push(String::from("$match"));
+
+        let last: usize = arms.len().saturating_sub(1);
+        let mut end_jumps: Vec<usize> = Vec::new();
+        for (i, (pattern, body)) in arms.into_iter().enumerate() {
+            let mut fail_jumps: Vec<usize> = Vec::new();
+            let mut bindings: Vec<(String, Vec<usize>)> = Vec::new();
+            // the typechecker proved the match exhaustive, so the last arm needs no tests
+            self.compile_pattern(
+                &pattern,
+                slot,
+                &mut Vec::new(),
+                i != last,
+                &mut fail_jumps,
+                &mut bindings,
+            )?;
+            for (name, path) in bindings {
+                self.load_path(slot, &path);
+                let binding: usize = self.locals().len();
+                self.code.push(OpCode::StoreLocal(binding));
+                self.locals().push(name);
+            }
+            let body = self.compile_expr(body);
+            self.locals().truncate(slot + 1);
+            body?;
+
+            end_jumps.push(self.code.len());
+            self.code.push(OpCode::Jump(0));
+            let next_arm: usize = self.code.len();
+            for pos in fail_jumps {
+                self.code[pos] = OpCode::JumpIfFalse(next_arm);
+            }
+        }
+
+        let end: usize = self.code.len();
+        for pos in end_jumps {
+            self.code[pos] = OpCode::Jump(end);
+        }
+        self.locals().pop();
+        Ok(())
+    }
+
+    fn compile_try(&mut self, body: Expr, name: String, handler: Expr) -> Result<(), CompileError> {
+        let try_pos: usize = self.code.len();
+        self.code.push(OpCode::Try(0)); // [Try(0)]
+        if let Some(current) = self.loops.last_mut() {
+            current.tries += 1;
+        }
+        let body = self.compile_expr(body);
+        if let Some(current) = self.loops.last_mut() {
+            current.tries -= 1;
+        }
+        body?;
+        self.code.push(OpCode::EndTry);
+        let jump_pos: usize = self.code.len();
+        self.code.push(OpCode::Jump(0)); // [Try(0), body_cmds, EndTry, Jump(0)]
+
+        // the VM pushes the error before jumping to the handler
+        let handler_pos: usize = self.code.len();
+        let slot: usize = self.locals().len();
+        self.code.push(OpCode::StoreLocal(slot));
+        self.locals().push(name);
+        let handler = self.compile_expr(handler);
+        self.locals().pop();
+        handler?; // [Try(0), body_cmds, EndTry, Jump(0), StoreLocal, handler_cmds]
+
+        let end: usize = self.code.len();
+        self.code[try_pos] = OpCode::Try(handler_pos);
+        self.code[jump_pos] = OpCode::Jump(end);
+        Ok(())
+    }
+
+    // removes the handlers of the `try` bodies that a `break` or `continue` jumps out of
+    fn exit_tries(&mut self) -> Result<(), CompileError> {
+        let current: &Loop = self.loops.last().ok_or(CompileError::BreakOutsideLoop)?;
+        for _ in 0..current.tries {
+            self.code.push(OpCode::EndTry);
+        }
+        Ok(())
+    }
+
+    // emits the tests of `pattern` when `check` is set and collects the paths of its variables
+    fn compile_pattern(
+        &mut self,
+        pattern: &Pattern,
+        slot: usize,
+        path: &mut Vec<usize>,
+        check: bool,
+        fail_jumps: &mut Vec<usize>,
+        bindings: &mut Vec<(String, Vec<usize>)>,
+    ) -> Result<(), CompileError> {
+        let test: OpCode = match pattern {
+            Pattern::Wildcard => return Ok(()),
+            Pattern::Var(name) => {
+                bindings.push((name.clone(), path.clone()));
+                return Ok(());
+            }
+            Pattern::Int(n) => OpCode::Push(TinyObject::Int(n.clone())),
+            Pattern::Bool(b) => OpCode::Push(TinyObject::Bool(*b)),
+            Pattern::String(s) => OpCode::Push(TinyObject::String(Rc::from(s.as_str()))),
+            Pattern::Constructor { name, args } => {
+                let tag: usize = *self
+                    .constructors
+                    .get(name)
+                    .ok_or_else(|| CompileError::UndefinedConstructor(name.clone()))?;
+                if check {
+                    self.load_path(slot, path);
+                    self.code.push(OpCode::IsVariant(tag));
+                    fail_jumps.push(self.code.len());
+                    self.code.push(OpCode::JumpIfFalse(0));
+                }
+                for (i, arg) in args.iter().enumerate() {
+                    path.push(i);
+                    self.compile_pattern(arg, slot, path, check, fail_jumps, bindings)?;
+                    path.pop();
+                }
+                return Ok(());
+            }
+        };
+        if check {
+            self.load_path(slot, path);
+            self.code.push(test);
+            self.code.push(OpCode::Equal);
+            fail_jumps.push(self.code.len());
+            self.code.push(OpCode::JumpIfFalse(0));
+        }
+        Ok(())
+    }
+
+    // pushes the value found by following the field indices of `path` from the scrutinee
+    fn load_path(&mut self, slot: usize, path: &[usize]) {
+        self.code.push(OpCode::LoadLocal(slot));
+        for field in path {
+            self.code.push(OpCode::VariantField(*field));
         }
     }
 
+    // keeps the tuple being destructured in a hidden local and returns its slot
+    fn compile_tuple(&mut self, value: Expr) -> Result<usize, CompileError> {
+        self.compile_expr(value)?;
+        let slot: usize = self.locals().len();
+        self.code.push(OpCode::StoreLocal(slot));
+        self.locals().push(String::from("$tuple"));
+        Ok(slot)
+    }
+
     fn compile_binop(&mut self, op: BinaryOperation) -> Result<(), CompileError> {
+        let (left, right, opcode) = match op {
+            BinaryOperation::Add { left, right } => (left, right, OpCode::Add),
+            BinaryOperation::Sub { left, right } => (left, right, OpCode::Sub),
+            BinaryOperation::Mul { left, right } => (left, right, OpCode::Mul),
+            BinaryOperation::Div { left, right } => (left, right, OpCode::Div),
+            BinaryOperation::Mod { left, right } => (left, right, OpCode::Mod),
+            BinaryOperation::Eq { left, right } => (left, right, OpCode::Equal),
+            BinaryOperation::Ne { left, right } => (left, right, OpCode::NotEqual),
+            BinaryOperation::Lt { left, right } => (left, right, OpCode::Less),
+            BinaryOperation::Le { left, right } => (left, right, OpCode::LessEqual),
+            BinaryOperation::Gt { left, right } => (left, right, OpCode::Greater),
+            BinaryOperation::Ge { left, right } => (left, right, OpCode::GreaterEqual),
+            BinaryOperation::And { left, right } => return self.compile_and(*left, *right),
+            BinaryOperation::Or { left, right } => return self.compile_or(*left, *right),
+        };
+        self.compile_expr(*left)?;
+        self.compile_expr(*right)?;
+        self.code.push(opcode);
+        Ok(())
+    }
+
+    fn compile_unaryop(&mut self, op: UnaryOperation) -> Result<(), CompileError> {
         match op {
-            BinaryOperation::Add { left, right } => {
-                self.compile_expr(*left)?;
-                self.compile_expr(*right)?;
-                self.code.push(OpCode::Add);
+            UnaryOperation::Not { expr } => {
+                self.compile_expr(*expr)?;
+                self.code.push(OpCode::Not);
                 Ok(())
             }
+            UnaryOperation::Neg { expr } => {
+                self.compile_expr(*expr)?;
+                self.code.push(OpCode::Negate);
+                Ok(())
+            }
+        }
+    }
+
+    // the right operand is only evaluated when the left one is true
+    fn compile_and(&mut self, left: Expr, right: Expr) -> Result<(), CompileError> {
+        self.compile_expr(left)?; // [left_cmds]
+        let jump_if_false_pos: usize = self.code.len();
+        self.code.push(OpCode::JumpIfFalse(0)); // [left_cmds, JumpIfFalse(0)]
+        self.compile_expr(right)?; // [left_cmds, JumpIfFalse(0), right_cmds]
+        let jump_pos: usize = self.code.len();
+        self.code.push(OpCode::Jump(0)); // [left_cmds, JumpIfFalse(0), right_cmds, Jump(0)]
+        let false_start: usize = self.code.len();
+        self.code.push(OpCode::Push(TinyObject::Bool(false))); // [left_cmds, JumpIfFalse(0), right_cmds, Jump(0), Push(false)]
+        let end: usize = self.code.len();
+        self.code[jump_if_false_pos] = OpCode::JumpIfFalse(false_start);
+        self.code[jump_pos] = OpCode::Jump(end);
+        Ok(())
+    }
+
+    // the right operand is only evaluated when the left one is false
+    fn compile_or(&mut self, left: Expr, right: Expr) -> Result<(), CompileError> {
+        self.compile_expr(left)?; // [left_cmds]
+        let jump_if_false_pos: usize = self.code.len();
+        self.code.push(OpCode::JumpIfFalse(0)); // [left_cmds, JumpIfFalse(0)]
+        self.code.push(OpCode::Push(TinyObject::Bool(true))); // [left_cmds, JumpIfFalse(0), Push(true)]
+        let jump_pos: usize = self.code.len();
+        self.code.push(OpCode::Jump(0)); // [left_cmds, JumpIfFalse(0), Push(true), Jump(0)]
+        let right_start: usize = self.code.len();
+        self.compile_expr(right)?; // [left_cmds, JumpIfFalse(0), Push(true), Jump(0), right_cmds]
+        let end: usize = self.code.len();
+        self.code[jump_if_false_pos] = OpCode::JumpIfFalse(right_start);
+        self.code[jump_pos] = OpCode::Jump(end);
+        Ok(())
+    }
+
+    fn compile_while(&mut self, cond: Expr, body: Expr) -> Result<(), CompileError> {
+        self.code.push(OpCode::EnterLoop); // [EnterLoop]
+        let start: usize = self.code.len();
+        self.compile_expr(cond)?; // [EnterLoop, cond_cmds]
+        let jump_if_false_pos: usize = self.code.len();
+        self.code.push(OpCode::JumpIfFalse(0)); // [EnterLoop, cond_cmds, JumpIfFalse(0)]
+        self.loops.push(Loop {
+            start,
+            breaks: Vec::new(),
+            tries: 0,
+        });
+        let body = self.compile_expr(body);
+        let current: Loop = self.loops.pop().ok_or(CompileError::BreakOutsideLoop)?;
+        body?; // [EnterLoop, cond_cmds, JumpIfFalse(0), body_cmds]
+        self.code.push(OpCode::Pop);
+        self.code.push(OpCode::Jump(start)); // [EnterLoop, cond_cmds, JumpIfFalse(0), body_cmds, Pop, Jump(start)]
+        let end: usize = self.code.len();
+        self.code.push(OpCode::ExitLoop);
+        self.code.push(OpCode::MakeTuple(0)); // [.., Jump(start), ExitLoop, MakeTuple(0)]
+        self.code[jump_if_false_pos] = OpCode::JumpIfFalse(end);
+        for pos in current.breaks {
+            self.code[pos] = OpCode::Break(end);
         }
+        Ok(())
     }
 
     fn compile_if(&mut self, cond: Expr, thn: Expr, els: Expr) -> Result<(), CompileError> {
@@ -14,42 +14,83 @@ use tokenizer::{Tokenizer, tokenize_error_to_message};
 use value_object::ast::Stmt;
 use value_object::opcode::OpCode;
 use value_object::token::Token;
-use vm::{VM, runtime_error_to_message};
+use vm::{
+    Config, MAX_DECIMAL_SCALE, VM, overflow_from_name, rounding_from_name, runtime_error_to_message,
+};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     println!("{}", format_args!("{:?}", args));
 
-    let tokens: Vec<Token> = Tokenizer::tokenize(args[1].as_str()).unwrap_or_else(|e| {
+    // `--overflow=trap|wrap|saturate|promote`, `--decimal-scale=N` and
+    // `--rounding=half-even|half-up|down|up|floor|ceiling` may appear anywhere;
+    // the other argument is the program
+    let mut config: Config = Config::default();
+    let mut program: Option<&str> = None;
+    for arg in &args[1..] {
+        if let Some(name) = arg.strip_prefix("--overflow=") {
+            config.overflow = overflow_from_name(name).unwrap_or_else(|| {
+                eprintln!("[Argument Error] unknown overflow behavior: {}", name);
+                exit(1)
+            });
+        } else if let Some(scale) = arg.strip_prefix("--decimal-scale=") {
+            config.decimal_scale = scale
+                .parse::<u32>()
+                .ok()
+                .filter(|scale| *scale <= MAX_DECIMAL_SCALE)
+                .unwrap_or_else(|| {
+                    eprintln!(
+                        "[Argument Error] decimal scale must be an integer from 0 to {}: {}",
+                        MAX_DECIMAL_SCALE, scale
+                    );
+                    exit(1)
+                });
+        } else if let Some(name) = arg.strip_prefix("--rounding=") {
+            config.rounding = rounding_from_name(name).unwrap_or_else(|| {
+                eprintln!("[Argument Error] unknown rounding mode: {}", name);
+                exit(1)
+            });
+        } else {
+            program = Some(arg);
+        }
+    }
+    let program: &str = program.unwrap_or_else(|| {
+        eprintln!("[Argument Error] no program given");
+        exit(1)
+    });
+
+    let tokens: Vec<Token> = Tokenizer::tokenize(program).unwrap_or_else(|e| {
         eprintln!("{}", tokenize_error_to_message(e));
         exit(1)
     });
     println!("{:?}", tokens);
 
-    let ast: Stmt = Parser::new(tokens).parse().unwrap_or_else(|e| {
+    let mut ast: Vec<Stmt> = Parser::new(tokens).parse().unwrap_or_else(|e| {
         eprintln!("[Parse Error] {}", parse_error_to_message(e));
         exit(1)
     });
     println!("{:?}", ast.clone());
 
-    TypeChecker::typecheck(ast.clone()).unwrap_or_else(|e| {
+    TypeChecker::typecheck(&mut ast).unwrap_or_else(|e| {
         eprintln!("[TypeCheck Error] {}", typecheck_error_to_message(e));
         exit(1)
     });
 
     let mut compiler: Compiler = Compiler::new();
-    let opcodes: Vec<OpCode> = compiler.compile_stmt(ast).unwrap_or_else(|e| {
+    let opcodes: Vec<OpCode> = compiler.compile(ast).unwrap_or_else(|e| {
         eprintln!("[Compile Error] {}", compile_error_to_message(e));
         exit(1)
     });
     println!("{:?}", opcodes.clone());
 
-    let mut vm = VM::new(opcodes);
+    let mut vm = VM::new(opcodes, config);
     let result = vm.run().unwrap_or_else(|e| {
         eprintln!("[Runtime Error] {}", runtime_error_to_message(e));
         exit(1)
     });
-    println!("{:?}", result.unwrap());
+    if let Some(result) = result {
+        println!("{:?}", result);
+    }
 
     exit(0);
 }
@@ -1,4 +1,4 @@
-use crate::value_object::ast::{BinaryOperation, Expr, Stmt};
+use crate::value_object::ast::{BinaryOperation, Expr, Pattern, Stmt, TypeExpr, UnaryOperation};
 use crate::value_object::token::{Token, token_to_string};
 
 #[derive(Debug)]
@@ -14,19 +14,26 @@ pub fn parse_error_to_message(e: ParseError) -> String {
     match e {
         ParseError::UnexpectedEOF => "Unexpected EOF".to_string(),
         ParseError::UnexpectedToken { expected, actual } => {
-            if expected.is_none() {
-                format!("Unexpected Token: actual: {}", token_to_string(actual))
-            } else {
+            if let Some(expected) = expected {
                 format!(
                     "Unexpected Token: expected: {}, actual: {}",
-                    token_to_string(expected.unwrap()),
+                    token_to_string(expected),
                     token_to_string(actual)
                 )
+            } else {
+                format!("Unexpected Token: actual: {}", token_to_string(actual))
             }
         }
     }
 }
 
+// what a `let` binds its value to
+enum LetTarget {
+    Name(String),
+    Tuple(Vec<String>),
+    Mutable(String),
+}
+
 pub struct Parser {
     tokens: Vec<Token>,
     pos: usize,
@@ -37,16 +44,39 @@ impl Parser {
         Self { tokens, pos: 0 }
     }
 
+    // line breaks only separate statements, so expressions look through them
     fn peek(&self) -> Option<&Token> {
+        self.peek_nth(0)
+    }
+
+    // postfix `(` and `[` must start on the same line as their operand
+    fn peek_adjacent(&self) -> Option<&Token> {
         self.tokens.get(self.pos)
     }
 
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens[self.pos.min(self.tokens.len())..]
+            .iter()
+            .filter(|t| **t != Token::Newline)
+            .nth(n)
+    }
+
     fn next(&mut self) -> Option<&Token> {
+        while let Some(Token::Newline) = self.tokens.get(self.pos) {
+            self.pos += 1;
+        }
         let tok = self.tokens.get(self.pos);
         self.pos += 1;
         tok
     }
 
+    fn at_separator(&self) -> bool {
+        matches!(
+            self.tokens.get(self.pos),
+            None | Some(Token::Newline) | Some(Token::KeywordSemicolon)
+        )
+    }
+
     fn expect(&mut self, expected: &Token) -> Result<(), ParseError> {
         match self.next() {
             Some(actual) if actual == expected => Ok(()),
@@ -60,13 +90,257 @@ impl Parser {
 }
 
 impl Parser {
-    pub fn parse(&mut self) -> Result<Stmt, ParseError> {
-        let expr = self.parse_expr()?;
-        Ok(Stmt::Expr(expr))
+    pub fn parse(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        let mut stmts: Vec<Stmt> = Vec::new();
+        loop {
+            while let Some(Token::KeywordSemicolon) = self.peek() {
+                self.next(); // consume ';'
+            }
+            if self.peek().is_none() {
+                return Ok(stmts);
+            }
+
+            stmts.push(self.parse_stmt()?);
+            if !self.at_separator() {
+                return Err(ParseError::UnexpectedToken {
+                    expected: Some(Token::KeywordSemicolon),
+                    actual: self.tokens[self.pos].clone(),
+                });
+            }
+        }
+    }
+
+    fn parse_stmt(&mut self) -> Result<Stmt, ParseError> {
+        match (self.peek(), self.peek_nth(1)) {
+            // `fn` followed by a name is a definition, `fn(` starts a lambda expression
+            (Some(Token::KeywordFn), Some(Token::Identifier(_))) => self.parse_fn_stmt(),
+            (Some(Token::KeywordType), _) => self.parse_type_stmt(),
+            (Some(Token::KeywordLet | Token::KeywordVar), _) => {
+                let (target, value) = self.parse_let_binding()?;
+                if let Some(Token::KeywordIn) = self.peek() {
+                    self.next(); // consume 'in'
+                    let body: Expr = self.parse_expr()?;
+                    Ok(Stmt::Expr(Self::let_expr(target, value, body)))
+                } else {
+                    Ok(match target {
+                        LetTarget::Name(name) => Stmt::Let { name, value },
+                        LetTarget::Tuple(names) => Stmt::LetTuple { names, value },
+                        LetTarget::Mutable(name) => Stmt::LetMut { name, value },
+                    })
+                }
+            }
+            _ => Ok(Stmt::Expr(self.parse_expr()?)),
+        }
+    }
+
+    fn parse_fn_stmt(&mut self) -> Result<Stmt, ParseError> {
+        self.expect(&Token::KeywordFn)?; // consume 'fn'
+        let name: String = self.parse_identifier()?;
+        let type_params: Vec<String> = self.parse_type_params()?;
+        let (params, param_types) = self.parse_params()?;
+        let ret: Option<TypeExpr> = self.parse_return_type()?;
+        self.expect(&Token::KeywordEqual)?; // consume '='
+        let body: Expr = Self::annotated(self.parse_expr()?, ret);
+
+        Ok(Stmt::Fn {
+            name,
+            type_params,
+            params,
+            param_types,
+            body,
+        })
+    }
+
+    // `-> Type` after the parameters
+    fn parse_return_type(&mut self) -> Result<Option<TypeExpr>, ParseError> {
+        if let Some(Token::KeywordArrow) = self.peek() {
+            self.next(); // consume '->'
+            return Ok(Some(self.parse_type()?));
+        }
+        Ok(None)
+    }
+
+    // `: Type` after a name
+    fn parse_annotation(&mut self) -> Result<Option<TypeExpr>, ParseError> {
+        if let Some(Token::KeywordColon) = self.peek() {
+            self.next(); // consume ':'
+            return Ok(Some(self.parse_type()?));
+        }
+        Ok(None)
+    }
+
+    fn annotated(expr: Expr, ty: Option<TypeExpr>) -> Expr {
+        match ty {
+            Some(ty) => Expr::Annotated {
+                expr: Box::new(expr),
+                ty,
+            },
+            None => expr,
+        }
+    }
+
+    // `type Shape = Circle(Float) | Rect(Float, Float)`
+    fn parse_type_stmt(&mut self) -> Result<Stmt, ParseError> {
+        self.expect(&Token::KeywordType)?; // consume 'type'
+        let name: String = self.parse_identifier()?;
+        let params: Vec<String> = self.parse_type_params()?;
+        self.expect(&Token::KeywordEqual)?; // consume '='
+        if let Some(Token::KeywordPipe) = self.peek() {
+            self.next(); // consume leading '|'
+        }
+        let mut variants: Vec<(String, Vec<TypeExpr>)> = vec![self.parse_variant()?];
+        while let Some(Token::KeywordPipe) = self.peek() {
+            self.next(); // consume '|'
+            variants.push(self.parse_variant()?);
+        }
+
+        Ok(Stmt::Type {
+            name,
+            params,
+            variants,
+        })
+    }
+
+    // `<A, B>` after the name of a function or a type
+    fn parse_type_params(&mut self) -> Result<Vec<String>, ParseError> {
+        let mut params: Vec<String> = Vec::new();
+        if let Some(Token::KeywordLess) = self.peek() {
+            self.next(); // consume '<'
+            params.push(self.parse_identifier()?);
+            while matches!(self.peek(), Some(Token::KeywordComma)) {
+                self.next(); // consume ','
+                params.push(self.parse_identifier()?);
+            }
+            self.expect(&Token::KeywordGreater)?; // consume '>'
+        }
+        Ok(params)
+    }
+
+    fn parse_variant(&mut self) -> Result<(String, Vec<TypeExpr>), ParseError> {
+        let name: String = self.parse_constructor_name()?;
+        let mut fields: Vec<TypeExpr> = Vec::new();
+        if let Some(Token::ParenLeft) = self.peek_adjacent() {
+            self.next(); // consume '('
+            fields.push(self.parse_type()?);
+            while matches!(self.peek(), Some(Token::KeywordComma)) {
+                self.next(); // consume ','
+                fields.push(self.parse_type()?);
+            }
+            self.expect(&Token::ParenRight)?; // consume ')'
+        }
+        Ok((name, fields))
+    }
+
+    // `Int`, `List<Int>`, `Shape`, `{ x: Float, y: Float }`, `(Int, Bool)` or `fn(Int) -> Int`
+    fn parse_type(&mut self) -> Result<TypeExpr, ParseError> {
+        match self.peek() {
+            Some(Token::ParenLeft) => {
+                let mut elements: Vec<TypeExpr> = self.parse_types()?;
+                return match elements.len() {
+                    1 => Ok(elements.remove(0)),
+                    _ => Ok(TypeExpr::Tuple(elements)),
+                };
+            }
+            Some(Token::KeywordFn) => {
+                self.next(); // consume 'fn'
+                let params: Vec<TypeExpr> = self.parse_types()?;
+                self.expect(&Token::KeywordArrow)?; // consume '->'
+                let ret: TypeExpr = self.parse_type()?;
+                return Ok(TypeExpr::Function {
+                    params,
+                    ret: Box::new(ret),
+                });
+            }
+            _ => {}
+        }
+
+        if let Some(Token::BraceLeft) = self.peek() {
+            self.next(); // consume '{'
+            let mut fields: Vec<(String, TypeExpr)> = Vec::new();
+            while !matches!(self.peek(), Some(Token::BraceRight)) {
+                let name: String = self.parse_identifier()?;
+                self.expect(&Token::KeywordColon)?; // consume ':'
+                fields.push((name, self.parse_type()?));
+                if !matches!(self.peek(), Some(Token::KeywordComma)) {
+                    break;
+                }
+                self.next(); // consume ','
+            }
+            self.expect(&Token::BraceRight)?; // consume '}'
+            return Ok(TypeExpr::Record(fields));
+        }
+
+        let name: String = self.parse_identifier()?;
+        let mut args: Vec<TypeExpr> = Vec::new();
+        if let Some(Token::KeywordLess) = self.peek() {
+            self.next(); // consume '<'
+            args.push(self.parse_type()?);
+            while matches!(self.peek(), Some(Token::KeywordComma)) {
+                self.next(); // consume ','
+                args.push(self.parse_type()?);
+            }
+            self.expect(&Token::KeywordGreater)?; // consume '>'
+        }
+        Ok(TypeExpr::Named { name, args })
+    }
+
+    // constructors are told apart from variables by their capitalized name
+    fn parse_constructor_name(&mut self) -> Result<String, ParseError> {
+        match self.next() {
+            Some(Token::Identifier(name)) if name.starts_with(|c: char| c.is_ascii_uppercase()) => {
+                Ok(name.clone())
+            }
+            Some(actual) => Err(ParseError::UnexpectedToken {
+                expected: Some(Token::Identifier(String::from("<Constructor>"))),
+                actual: actual.clone(),
+            }),
+            None => Err(ParseError::UnexpectedEOF),
+        }
+    }
+
+    // comma separated types between parentheses
+    fn parse_types(&mut self) -> Result<Vec<TypeExpr>, ParseError> {
+        self.expect(&Token::ParenLeft)?; // consume '('
+        let mut types: Vec<TypeExpr> = Vec::new();
+        if !matches!(self.peek(), Some(Token::ParenRight)) {
+            types.push(self.parse_type()?);
+            while matches!(self.peek(), Some(Token::KeywordComma)) {
+                self.next(); // consume ','
+                types.push(self.parse_type()?);
+            }
+        }
+        self.expect(&Token::ParenRight)?; // consume ')'
+        Ok(types)
+    }
+
+    // parameter names with their optional annotations
+    fn parse_params(&mut self) -> Result<(Vec<String>, Vec<Option<TypeExpr>>), ParseError> {
+        self.expect(&Token::ParenLeft)?; // consume '('
+        let mut params: Vec<String> = Vec::new();
+        let mut param_types: Vec<Option<TypeExpr>> = Vec::new();
+        if !matches!(self.peek(), Some(Token::ParenRight)) {
+            params.push(self.parse_identifier()?);
+            param_types.push(self.parse_annotation()?);
+            while matches!(self.peek(), Some(Token::KeywordComma)) {
+                self.next(); // consume ','
+                params.push(self.parse_identifier()?);
+                param_types.push(self.parse_annotation()?);
+            }
+        }
+        self.expect(&Token::ParenRight)?; // consume ')'
+        Ok((params, param_types))
     }
 
     fn parse_expr(&mut self) -> Result<Expr, ParseError> {
-        let expr = self.parse_add_expr()?;
+        match (self.peek(), self.peek_nth(1)) {
+            (Some(Token::KeywordLet | Token::KeywordVar), _) => return self.parse_let_expr(),
+            (Some(Token::Identifier(_)), Some(Token::KeywordEqual)) => {
+                return self.parse_assign_expr();
+            }
+            _ => {}
+        }
+
+        let expr = self.parse_default_expr()?;
 
         if let Some(Token::KeywordQuestion) = self.peek() {
             Ok(self.parse_if_expr(expr))?
@@ -88,12 +362,324 @@ impl Parser {
         })
     }
 
-    fn parse_add_expr(&mut self) -> Result<Expr, ParseError> {
-        let mut left: Expr = self.parse_term()?;
-        while matches!(self.peek(), Some(Token::KeywordPlus)) {
-            self.next(); // consume '+'
-            let right: Expr = self.parse_term()?;
-            left = Expr::BinOp(Box::new(BinaryOperation::Add {
+    // `let x = value`, `let (a, b) = value` or `var x = value`, each with an optional `: Type`
+    fn parse_let_binding(&mut self) -> Result<(LetTarget, Expr), ParseError> {
+        if let Some(Token::KeywordVar) = self.peek() {
+            self.next(); // consume 'var'
+            let name: String = self.parse_identifier()?;
+            let ty: Option<TypeExpr> = self.parse_annotation()?;
+            self.expect(&Token::KeywordEqual)?; // consume '='
+            let value: Expr = Self::annotated(self.parse_expr()?, ty);
+            return Ok((LetTarget::Mutable(name), value));
+        }
+
+        self.expect(&Token::KeywordLet)?; // consume 'let'
+        let target: LetTarget = if let Some(Token::ParenLeft) = self.peek() {
+            self.next(); // consume '('
+            let mut names: Vec<String> = vec![self.parse_identifier()?];
+            while matches!(self.peek(), Some(Token::KeywordComma)) {
+                self.next(); // consume ','
+                names.push(self.parse_identifier()?);
+            }
+            self.expect(&Token::ParenRight)?; // consume ')'
+            LetTarget::Tuple(names)
+        } else {
+            LetTarget::Name(self.parse_identifier()?)
+        };
+        let ty: Option<TypeExpr> = self.parse_annotation()?;
+        self.expect(&Token::KeywordEqual)?; // consume '='
+        let value: Expr = Self::annotated(self.parse_expr()?, ty);
+        Ok((target, value))
+    }
+
+    fn parse_let_expr(&mut self) -> Result<Expr, ParseError> {
+        let (target, value) = self.parse_let_binding()?;
+        self.expect(&Token::KeywordIn)?; // consume 'in'
+        let body: Expr = self.parse_expr()?;
+
+        Ok(Self::let_expr(target, value, body))
+    }
+
+    fn let_expr(target: LetTarget, value: Expr, body: Expr) -> Expr {
+        match target {
+            LetTarget::Name(name) => Expr::Let {
+                name,
+                value: Box::new(value),
+                body: Box::new(body),
+            },
+            LetTarget::Tuple(names) => Expr::LetTuple {
+                names,
+                value: Box::new(value),
+                body: Box::new(body),
+            },
+            LetTarget::Mutable(name) => Expr::LetMut {
+                name,
+                value: Box::new(value),
+                body: Box::new(body),
+            },
+        }
+    }
+
+    fn parse_assign_expr(&mut self) -> Result<Expr, ParseError> {
+        let name: String = self.parse_identifier()?;
+        self.expect(&Token::KeywordEqual)?; // consume '='
+        let value: Expr = self.parse_expr()?;
+
+        Ok(Expr::Assign {
+            name,
+            value: Box::new(value),
+        })
+    }
+
+    fn parse_while_expr(&mut self) -> Result<Expr, ParseError> {
+        self.expect(&Token::KeywordWhile)?; // consume 'while'
+        let cond: Expr = self.parse_expr()?;
+        self.expect(&Token::BraceLeft)?; // consume '{'
+        let body: Expr = self.parse_block(Vec::new())?;
+
+        Ok(Expr::While {
+            cond: Box::new(cond),
+            body: Box::new(body),
+        })
+    }
+
+    fn parse_try_expr(&mut self) -> Result<Expr, ParseError> {
+        self.expect(&Token::KeywordTry)?; // consume 'try'
+        self.expect(&Token::BraceLeft)?; // consume '{'
+        let body: Expr = self.parse_block(Vec::new())?;
+        self.expect(&Token::KeywordCatch)?; // consume 'catch'
+        let name: String = self.parse_identifier()?;
+        self.expect(&Token::BraceLeft)?; // consume '{'
+        let handler: Expr = self.parse_block(Vec::new())?;
+
+        Ok(Expr::Try {
+            body: Box::new(body),
+            name,
+            handler: Box::new(handler),
+        })
+    }
+
+    // the items of a block after its '{' up to and including the closing '}';
+    // a `let` or `var` without `in` scopes over the rest of the block
+    fn parse_block(&mut self, mut items: Vec<Expr>) -> Result<Expr, ParseError> {
+        loop {
+            while let Some(Token::KeywordSemicolon) = self.peek() {
+                self.next(); // consume ';'
+            }
+            if let Some(Token::BraceRight) = self.peek() {
+                self.next(); // consume '}'
+                break;
+            }
+
+            let item: Expr = if let Some(Token::KeywordLet | Token::KeywordVar) = self.peek() {
+                let (target, value) = self.parse_let_binding()?;
+                if let Some(Token::KeywordIn) = self.peek() {
+                    self.next(); // consume 'in'
+                    let body: Expr = self.parse_expr()?;
+                    Self::let_expr(target, value, body)
+                } else {
+                    let rest: Expr = self.parse_block(Vec::new())?;
+                    items.push(Self::let_expr(target, value, rest));
+                    break;
+                }
+            } else {
+                self.parse_expr()?
+            };
+            items.push(item);
+
+            if !self.at_separator() && !matches!(self.peek(), Some(Token::BraceRight)) {
+                return Err(ParseError::UnexpectedToken {
+                    expected: Some(Token::KeywordSemicolon),
+                    actual: self.tokens[self.pos].clone(),
+                });
+            }
+        }
+
+        match items.len() {
+            0 => Ok(Expr::Tuple(Vec::new())),
+            1 => Ok(items.remove(0)),
+            _ => Ok(Expr::Block(items)),
+        }
+    }
+
+    fn parse_lambda_expr(&mut self) -> Result<Expr, ParseError> {
+        self.expect(&Token::KeywordFn)?; // consume 'fn'
+        let (params, param_types) = self.parse_params()?;
+        let ret: Option<TypeExpr> = self.parse_return_type()?;
+        self.expect(&Token::KeywordFatArrow)?; // consume '=>'
+        let body: Expr = Self::annotated(self.parse_expr()?, ret);
+
+        Ok(Expr::Lambda {
+            params,
+            param_types,
+            body: Box::new(body),
+        })
+    }
+
+    // `{ name: expr, ... }`, `{ record with name: expr, ... }` or a block `{ expr; ... }`
+    fn parse_record_expr(&mut self) -> Result<Expr, ParseError> {
+        self.expect(&Token::BraceLeft)?; // consume '{'
+        let is_literal: bool = matches!(self.peek(), Some(Token::BraceRight))
+            || matches!(
+                (self.peek(), self.peek_nth(1)),
+                (Some(Token::Identifier(_)), Some(Token::KeywordColon))
+            );
+        if is_literal {
+            let fields: Vec<(String, Expr)> = self.parse_fields()?;
+            return Ok(Expr::Record(fields));
+        }
+        if let Some(Token::KeywordLet | Token::KeywordVar) = self.peek() {
+            return self.parse_block(Vec::new());
+        }
+
+        // anything but `{ record with ... }` is a block
+        let record: Expr = self.parse_expr()?;
+        if !matches!(self.peek(), Some(Token::KeywordWith)) {
+            if !self.at_separator() && !matches!(self.peek(), Some(Token::BraceRight)) {
+                return Err(ParseError::UnexpectedToken {
+                    expected: Some(Token::KeywordWith),
+                    actual: self.tokens[self.pos].clone(),
+                });
+            }
+            return self.parse_block(vec![record]);
+        }
+        self.expect(&Token::KeywordWith)?; // consume 'with'
+        let fields: Vec<(String, Expr)> = self.parse_fields()?;
+        Ok(Expr::RecordUpdate {
+            record: Box::new(record),
+            fields,
+            offsets: None,
+        })
+    }
+
+    // comma separated `name: expr` pairs up to and including the closing '}'
+    fn parse_fields(&mut self) -> Result<Vec<(String, Expr)>, ParseError> {
+        let mut fields: Vec<(String, Expr)> = Vec::new();
+        while !matches!(self.peek(), Some(Token::BraceRight)) {
+            let name: String = self.parse_identifier()?;
+            self.expect(&Token::KeywordColon)?; // consume ':'
+            fields.push((name, self.parse_expr()?));
+            if !matches!(self.peek(), Some(Token::KeywordComma)) {
+                break;
+            }
+            self.next(); // consume ','
+        }
+        self.expect(&Token::BraceRight)?; // consume '}'
+        Ok(fields)
+    }
+
+    // `match expr { pattern => expr, ... }`; arms may also be separated by newlines
+    fn parse_match_expr(&mut self) -> Result<Expr, ParseError> {
+        self.expect(&Token::KeywordMatch)?; // consume 'match'
+        let scrutinee: Expr = self.parse_expr()?;
+        self.expect(&Token::BraceLeft)?; // consume '{'
+        let mut arms: Vec<(Pattern, Expr)> = Vec::new();
+        while !matches!(self.peek(), Some(Token::BraceRight)) {
+            let pattern: Pattern = self.parse_pattern()?;
+            self.expect(&Token::KeywordFatArrow)?; // consume '=>'
+            arms.push((pattern, self.parse_expr()?));
+            if let Some(Token::KeywordComma) = self.peek() {
+                self.next(); // consume ','
+            }
+        }
+        self.expect(&Token::BraceRight)?; // consume '}'
+
+        Ok(Expr::Match {
+            scrutinee: Box::new(scrutinee),
+            arms,
+        })
+    }
+
+    fn parse_pattern(&mut self) -> Result<Pattern, ParseError> {
+        match self.peek() {
+            Some(Token::Identifier(name)) if name == "_" => {
+                self.next(); // consume '_'
+                Ok(Pattern::Wildcard)
+            }
+            Some(Token::Identifier(name)) if name.starts_with(|c: char| c.is_ascii_uppercase()) => {
+                let name: String = self.parse_constructor_name()?;
+                let mut args: Vec<Pattern> = Vec::new();
+                if let Some(Token::ParenLeft) = self.peek_adjacent() {
+                    self.next(); // consume '('
+                    args.push(self.parse_pattern()?);
+                    while matches!(self.peek(), Some(Token::KeywordComma)) {
+                        self.next(); // consume ','
+                        args.push(self.parse_pattern()?);
+                    }
+                    self.expect(&Token::ParenRight)?; // consume ')'
+                }
+                Ok(Pattern::Constructor { name, args })
+            }
+            Some(Token::KeywordMinus) => {
+                self.next(); // consume '-'
+                match self.next() {
+                    Some(Token::LiteralInt(n)) => Ok(Pattern::Int(-n)),
+                    Some(actual) => Err(ParseError::UnexpectedToken {
+                        expected: None,
+                        actual: actual.clone(),
+                    }),
+                    None => Err(ParseError::UnexpectedEOF),
+                }
+            }
+            _ => match self.next() {
+                Some(Token::Identifier(name)) => Ok(Pattern::Var(name.clone())),
+                Some(Token::LiteralInt(n)) => Ok(Pattern::Int(n.clone())),
+                Some(Token::LiteralBool(b)) => Ok(Pattern::Bool(*b)),
+                Some(Token::LiteralString(s)) => Ok(Pattern::String(s.clone())),
+                Some(actual) => Err(ParseError::UnexpectedToken {
+                    expected: None,
+                    actual: actual.clone(),
+                }),
+                None => Err(ParseError::UnexpectedEOF),
+            },
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Result<String, ParseError> {
+        match self.next() {
+            Some(Token::Identifier(name)) => Ok(name.clone()),
+            Some(actual) => Err(ParseError::UnexpectedToken {
+                expected: Some(Token::Identifier(String::from("<identifier>"))),
+                actual: actual.clone(),
+            }),
+            None => Err(ParseError::UnexpectedEOF),
+        }
+    }
+
+    // `??` is a token of its own, so it never starts a ternary; it groups to the right so that
+    // `a ?? b ?? c` tries `a`, then `b`, then falls back to `c`
+    fn parse_default_expr(&mut self) -> Result<Expr, ParseError> {
+        let option: Expr = self.parse_or_expr()?;
+        if let Some(Token::KeywordDoubleQuestion) = self.peek() {
+            self.next(); // consume '??'
+            let fallback: Expr = self.parse_default_expr()?;
+            return Ok(Expr::Default {
+                option: Box::new(option),
+                fallback: Box::new(fallback),
+            });
+        }
+        Ok(option)
+    }
+
+    fn parse_or_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut left: Expr = self.parse_and_expr()?;
+        while matches!(self.peek(), Some(Token::KeywordOrOr)) {
+            self.next(); // consume '||'
+            let right: Expr = self.parse_and_expr()?;
+            left = Expr::BinOp(Box::new(BinaryOperation::Or {
+                left: Box::new(left),
+                right: Box::new(right),
+            }));
+        }
+        Ok(left)
+    }
+
+    fn parse_and_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut left: Expr = self.parse_equality_expr()?;
+        while matches!(self.peek(), Some(Token::KeywordAndAnd)) {
+            self.next(); // consume '&&'
+            let right: Expr = self.parse_equality_expr()?;
+            left = Expr::BinOp(Box::new(BinaryOperation::And {
                 left: Box::new(left),
                 right: Box::new(right),
             }));
@@ -101,13 +687,203 @@ impl Parser {
         Ok(left)
     }
 
+    fn parse_equality_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut left: Expr = self.parse_comparison_expr()?;
+        loop {
+            let op: fn(Box<Expr>, Box<Expr>) -> BinaryOperation = match self.peek() {
+                Some(Token::KeywordDoubleEqual) => {
+                    |left, right| BinaryOperation::Eq { left, right }
+                }
+                Some(Token::KeywordNotEqual) => |left, right| BinaryOperation::Ne { left, right },
+                _ => return Ok(left),
+            };
+            self.next(); // consume '==' or '!='
+            let right: Expr = self.parse_comparison_expr()?;
+            left = Expr::BinOp(Box::new(op(Box::new(left), Box::new(right))));
+        }
+    }
+
+    fn parse_comparison_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut left: Expr = self.parse_add_expr()?;
+        loop {
+            let op: fn(Box<Expr>, Box<Expr>) -> BinaryOperation = match self.peek() {
+                Some(Token::KeywordLess) => |left, right| BinaryOperation::Lt { left, right },
+                Some(Token::KeywordLessEqual) => |left, right| BinaryOperation::Le { left, right },
+                Some(Token::KeywordGreater) => |left, right| BinaryOperation::Gt { left, right },
+                Some(Token::KeywordGreaterEqual) => {
+                    |left, right| BinaryOperation::Ge { left, right }
+                }
+                _ => return Ok(left),
+            };
+            self.next(); // consume '<', '<=', '>' or '>='
+            let right: Expr = self.parse_add_expr()?;
+            left = Expr::BinOp(Box::new(op(Box::new(left), Box::new(right))));
+        }
+    }
+
+    fn parse_add_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut left: Expr = self.parse_mul_expr()?;
+        loop {
+            // a line that starts with `-` or `+` is a new statement, like `1\n-2`
+            let op: fn(Box<Expr>, Box<Expr>) -> BinaryOperation = match self.peek_adjacent() {
+                Some(Token::KeywordPlus) => |left, right| BinaryOperation::Add { left, right },
+                Some(Token::KeywordMinus) => |left, right| BinaryOperation::Sub { left, right },
+                _ => return Ok(left),
+            };
+            self.next(); // consume '+' or '-'
+            let right: Expr = self.parse_mul_expr()?;
+            left = Expr::BinOp(Box::new(op(Box::new(left), Box::new(right))));
+        }
+    }
+
+    fn parse_mul_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut left: Expr = self.parse_cast_expr()?;
+        loop {
+            let op: fn(Box<Expr>, Box<Expr>) -> BinaryOperation = match self.peek() {
+                Some(Token::KeywordStar) => |left, right| BinaryOperation::Mul { left, right },
+                Some(Token::KeywordSlash) => |left, right| BinaryOperation::Div { left, right },
+                Some(Token::KeywordPercent) => |left, right| BinaryOperation::Mod { left, right },
+                _ => return Ok(left),
+            };
+            self.next(); // consume '*', '/' or '%'
+            let right: Expr = self.parse_cast_expr()?;
+            left = Expr::BinOp(Box::new(op(Box::new(left), Box::new(right))));
+        }
+    }
+
+    // `-price as Decimal` converts `-price`; casts chain to the left
+    fn parse_cast_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut expr: Expr = self.parse_unary_expr()?;
+        while let Some(Token::KeywordAs) = self.peek() {
+            self.next(); // consume 'as'
+            let target: TypeExpr = self.parse_type()?;
+            expr = Expr::Convert {
+                expr: Box::new(expr),
+                target,
+            };
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary_expr(&mut self) -> Result<Expr, ParseError> {
+        match self.peek() {
+            Some(Token::KeywordBang) => {
+                self.next(); // consume '!'
+                let expr: Expr = self.parse_unary_expr()?;
+                Ok(Expr::UnaryOp(Box::new(UnaryOperation::Not {
+                    expr: Box::new(expr),
+                })))
+            }
+            Some(Token::KeywordMinus) => {
+                self.next(); // consume '-'
+                match self.parse_unary_expr()? {
+                    // a negative literal, so that `-9223372036854775808` fits in 64 bits
+                    Expr::Int(n) => Ok(Expr::Int(-&n)),
+                    expr => Ok(Expr::UnaryOp(Box::new(UnaryOperation::Neg {
+                        expr: Box::new(expr),
+                    }))),
+                }
+            }
+            _ => self.parse_postfix_expr(),
+        }
+    }
+
+    fn parse_postfix_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut expr: Expr = self.parse_term()?;
+        loop {
+            match self.peek_adjacent() {
+                Some(Token::ParenLeft) => {
+                    let args: Vec<Expr> = self.parse_args(&Token::ParenLeft, &Token::ParenRight)?;
+                    expr = Expr::Call {
+                        callee: Box::new(expr),
+                        args,
+                    };
+                }
+                Some(Token::BracketLeft) => {
+                    self.next(); // consume '['
+                    let index: Expr = self.parse_expr()?;
+                    if let Some(Token::KeywordDotDot) = self.peek() {
+                        self.next(); // consume '..'
+                        let end: Expr = self.parse_expr()?;
+                        expr = Expr::Slice {
+                            expr: Box::new(expr),
+                            start: Box::new(index),
+                            end: Box::new(end),
+                        };
+                    } else {
+                        expr = Expr::Index {
+                            expr: Box::new(expr),
+                            index: Box::new(index),
+                        };
+                    }
+                    self.expect(&Token::BracketRight)?; // consume ']'
+                }
+                Some(Token::KeywordDot) => {
+                    self.next(); // consume '.'
+                    let field: String = self.parse_identifier()?;
+                    expr = Expr::Field {
+                        expr: Box::new(expr),
+                        field,
+                        offset: None,
+                    };
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    // comma separated expressions between `open` and `close`
+    fn parse_args(&mut self, open: &Token, close: &Token) -> Result<Vec<Expr>, ParseError> {
+        self.expect(open)?;
+        let mut args: Vec<Expr> = Vec::new();
+        if self.peek() != Some(close) {
+            args.push(self.parse_expr()?);
+            while matches!(self.peek(), Some(Token::KeywordComma)) {
+                self.next(); // consume ','
+                args.push(self.parse_expr()?);
+            }
+        }
+        self.expect(close)?;
+        Ok(args)
+    }
+
     fn parse_term(&mut self) -> Result<Expr, ParseError> {
         match self.peek() {
+            // `(expr)` only groups, a comma makes it a tuple
             Some(Token::ParenLeft) => {
-                self.next(); // consume '('
-                let expr = self.parse_expr()?;
+                let mut elements: Vec<Expr> =
+                    self.parse_args(&Token::ParenLeft, &Token::ParenRight)?;
+                match elements.len() {
+                    1 => Ok(elements.remove(0)),
+                    _ => Ok(Expr::Tuple(elements)),
+                }
+            }
+            Some(Token::KeywordFn) => self.parse_lambda_expr(),
+            Some(Token::KeywordMatch) => self.parse_match_expr(),
+            Some(Token::KeywordWhile) => self.parse_while_expr(),
+            Some(Token::KeywordTry) => self.parse_try_expr(),
+            Some(Token::KeywordThrow) => {
+                self.next(); // consume 'throw'
+                Ok(Expr::Throw(Box::new(self.parse_expr()?)))
+            }
+            Some(Token::KeywordBreak) => {
+                self.next(); // consume 'break'
+                Ok(Expr::Break)
+            }
+            Some(Token::KeywordContinue) => {
+                self.next(); // consume 'continue'
+                Ok(Expr::Continue)
+            }
+            Some(Token::BracketLeft) => Ok(Expr::List(
+                self.parse_args(&Token::BracketLeft, &Token::BracketRight)?,
+            )),
+            Some(Token::BraceLeft) => self.parse_record_expr(),
+            Some(Token::KeywordLen) => {
+                self.next(); // consume 'len'
+                self.expect(&Token::ParenLeft)?;
+                let expr: Expr = self.parse_expr()?;
                 self.expect(&Token::ParenRight)?;
-                Ok(expr)
+                Ok(Expr::Len(Box::new(expr)))
             }
             _ => self.parse_primary_expr(),
         }
@@ -115,14 +891,12 @@ impl Parser {
 
     fn parse_primary_expr(&mut self) -> Result<Expr, ParseError> {
         match self.next() {
-            Some(Token::LiteralInt(n)) => Ok(Expr::Int(*n)),
+            Some(Token::LiteralInt(n)) => Ok(Expr::Int(n.clone())),
             Some(Token::LiteralBool(b)) => Ok(Expr::Bool(*b)),
             Some(Token::LiteralFloat(f)) => Ok(Expr::Float(*f)),
-            Some(Token::ParenLeft) => {
-                let expr: Expr = self.parse_expr()?;
-                self.expect(&Token::ParenRight)?;
-                Ok(expr)
-            }
+            Some(Token::LiteralDecimal(d)) => Ok(Expr::Decimal(d.clone())),
+            Some(Token::LiteralString(s)) => Ok(Expr::String(s.clone())),
+            Some(Token::Identifier(name)) => Ok(Expr::Var(name.clone())),
             Some(actual) => Err(ParseError::UnexpectedToken {
                 expected: None,
                 actual: actual.clone(),
@@ -131,3 +905,34 @@ impl Parser {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::Tokenizer;
+    use crate::value_object::tiny_int::TinyInt;
+
+    fn parse(program: &str) -> Vec<Stmt> {
+        let tokens = Tokenizer::tokenize(program).unwrap_or_else(|_| panic!("{}", program));
+        Parser::new(tokens)
+            .parse()
+            .unwrap_or_else(|e| panic!("{}", parse_error_to_message(e)))
+    }
+
+    #[test]
+    fn minus_at_line_start_begins_a_statement() {
+        let stmts: Vec<Stmt> = parse("1\n-2");
+        assert_eq!(stmts.len(), 2);
+        assert!(matches!(&stmts[1], Stmt::Expr(Expr::Int(n)) if *n == TinyInt::from(-2)));
+    }
+
+    #[test]
+    fn minus_on_the_same_line_is_binary() {
+        let stmts: Vec<Stmt> = parse("1 -2");
+        assert_eq!(stmts.len(), 1);
+        assert!(matches!(
+            &stmts[0],
+            Stmt::Expr(Expr::BinOp(op)) if matches!(**op, BinaryOperation::Sub { .. })
+        ));
+    }
+}
@@ -1,17 +1,66 @@
+use crate::value_object::tiny_decimal::TinyDecimal;
+use crate::value_object::tiny_int::TinyInt;
 use crate::value_object::token::Token;
 use tailcall::tailcall;
 
 pub enum TokenizeError {
-    ParseIntError,
+    // the whole text of a malformed numeric literal and the byte offset where it starts
+    InvalidNumber {
+        literal: String,
+        offset: usize,
+        error: NumberError,
+    },
     UnexpectedCharacter(char),
-    UnexpectedKeyword(String),
+    UnterminatedString,
+    InvalidEscape(String),
+}
+
+pub enum NumberError {
+    InvalidDigit(char),
+    MissingDigits,
+    MisplacedSeparator,
+    MultipleDecimalPoints,
+    DoubledMinus,
+    ExponentInDecimal,
+    // a Float literal too large for f64
+    OutOfRange,
 }
 
 pub fn tokenize_error_to_message(e: TokenizeError) -> String {
     match e {
-        TokenizeError::ParseIntError => "Failed to parse int".to_string(),
+        TokenizeError::InvalidNumber {
+            literal,
+            offset,
+            error,
+        } => format!(
+            "{}: {} (at byte {})",
+            number_error_to_message(error),
+            literal,
+            offset
+        ),
         TokenizeError::UnexpectedCharacter(c) => format!("Unexpected character: {}", c),
-        TokenizeError::UnexpectedKeyword(k) => format!("Unexpected keyword: {}", k),
+        TokenizeError::UnterminatedString => "Unterminated string literal".to_string(),
+        TokenizeError::InvalidEscape(e) => format!("Invalid escape sequence: {}", e),
+    }
+}
+
+fn number_error_to_message(e: NumberError) -> String {
+    match e {
+        NumberError::InvalidDigit(digit) => {
+            format!("Invalid digit '{}' in numeric literal", digit)
+        }
+        NumberError::MissingDigits => "Missing digits in numeric literal".to_string(),
+        NumberError::MisplacedSeparator => {
+            "Digit separator '_' must be between two digits".to_string()
+        }
+        NumberError::MultipleDecimalPoints => {
+            "More than one decimal point in numeric literal".to_string()
+        }
+        NumberError::DoubledMinus => {
+            "Doubled minus sign, write `- -` or `-(-...)` instead".to_string()
+        }
+        NumberError::ExponentInDecimal => "Decimal literal cannot have an exponent".to_string(),
+        NumberError::OutOfRange => "Float literal is out of range".to_string(),
     }
 }
 
@@ -20,11 +69,13 @@ pub(crate) struct Tokenizer;
 impl Tokenizer {
     #[tailcall]
     pub fn tokenize(stream: &str) -> Result<Vec<Token>, TokenizeError> {
-        Self::tokenize_recursive(stream.trim_start(), vec![])
+        Self::tokenize_recursive(stream, stream.trim_start(), vec![])
     }
 
+    // `source` is the whole program, which error offsets count from
     #[tailcall]
     fn tokenize_recursive(
+        source: &str,
         stream: &str,
         mut tokens: Vec<Token>,
     ) -> Result<Vec<Token>, TokenizeError> {
@@ -39,86 +90,431 @@ impl Tokenizer {
         match first {
             '(' => {
                 tokens.push(Token::ParenLeft);
-                Self::tokenize_recursive(rest, tokens)
+                Self::tokenize_recursive(source, rest, tokens)
             }
             ')' => {
                 tokens.push(Token::ParenRight);
-                Self::tokenize_recursive(rest, tokens)
+                Self::tokenize_recursive(source, rest, tokens)
+            }
+            '[' => {
+                tokens.push(Token::BracketLeft);
+                Self::tokenize_recursive(source, rest, tokens)
+            }
+            ']' => {
+                tokens.push(Token::BracketRight);
+                Self::tokenize_recursive(source, rest, tokens)
+            }
+            '.' => match rest.strip_prefix('.') {
+                Some(rest) => {
+                    tokens.push(Token::KeywordDotDot);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+                // `.5` is a Float
+                None if rest.starts_with(|c: char| c.is_ascii_digit()) => {
+                    match parse_number_token(stream, source.len() - stream.len()) {
+                        Ok((token, rest)) => {
+                            tokens.push(token);
+                            Self::tokenize_recursive(source, rest, tokens)
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+                None => {
+                    tokens.push(Token::KeywordDot);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+            },
+            '{' => {
+                tokens.push(Token::BraceLeft);
+                Self::tokenize_recursive(source, rest, tokens)
+            }
+            '}' => {
+                tokens.push(Token::BraceRight);
+                Self::tokenize_recursive(source, rest, tokens)
             }
             '+' => {
                 tokens.push(Token::KeywordPlus);
-                Self::tokenize_recursive(rest, tokens)
+                Self::tokenize_recursive(source, rest, tokens)
+            }
+            '-' => match rest.strip_prefix('>') {
+                Some(rest) => {
+                    tokens.push(Token::KeywordArrow);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+                None if rest.starts_with('-')
+                    && rest[1..].starts_with(|c: char| c.is_ascii_digit() || c == '.') =>
+                {
+                    let (literal, _) = scan_number(&rest[1..]);
+                    Err(TokenizeError::InvalidNumber {
+                        literal: format!("--{}", literal),
+                        offset: source.len() - stream.len(),
+                        error: NumberError::DoubledMinus,
+                    })
+                }
+                None => {
+                    tokens.push(Token::KeywordMinus);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+            },
+            '*' => {
+                tokens.push(Token::KeywordStar);
+                Self::tokenize_recursive(source, rest, tokens)
+            }
+            '/' => {
+                tokens.push(Token::KeywordSlash);
+                Self::tokenize_recursive(source, rest, tokens)
+            }
+            '%' => {
+                tokens.push(Token::KeywordPercent);
+                Self::tokenize_recursive(source, rest, tokens)
+            }
+            ',' => {
+                tokens.push(Token::KeywordComma);
+                Self::tokenize_recursive(source, rest, tokens)
             }
             ':' => {
                 tokens.push(Token::KeywordColon);
-                Self::tokenize_recursive(rest, tokens)
+                Self::tokenize_recursive(source, rest, tokens)
             }
-            '?' => {
-                tokens.push(Token::KeywordQuestion);
-                Self::tokenize_recursive(rest, tokens)
+            ';' => {
+                tokens.push(Token::KeywordSemicolon);
+                Self::tokenize_recursive(source, rest, tokens)
             }
-            c if c.is_whitespace() => Self::tokenize_recursive(rest, tokens),
-            c if c.is_ascii_digit() || c == '-' => match parse_int_token(stream, c) {
-                Ok((token, rest)) => {
-                    tokens.push(token);
-                    Self::tokenize_recursive(rest, tokens)
+            '\n' => {
+                tokens.push(Token::Newline);
+                Self::tokenize_recursive(source, rest, tokens)
+            }
+            '?' => match rest.chars().next() {
+                Some('?') => {
+                    tokens.push(Token::KeywordDoubleQuestion);
+                    Self::tokenize_recursive(source, &rest[1..], tokens)
+                }
+                _ => {
+                    tokens.push(Token::KeywordQuestion);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+            },
+            '=' => match rest.chars().next() {
+                Some('>') => {
+                    tokens.push(Token::KeywordFatArrow);
+                    Self::tokenize_recursive(source, &rest[1..], tokens)
+                }
+                Some('=') => {
+                    tokens.push(Token::KeywordDoubleEqual);
+                    Self::tokenize_recursive(source, &rest[1..], tokens)
+                }
+                _ => {
+                    tokens.push(Token::KeywordEqual);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+            },
+            '!' => match rest.strip_prefix('=') {
+                Some(rest) => {
+                    tokens.push(Token::KeywordNotEqual);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+                None => {
+                    tokens.push(Token::KeywordBang);
+                    Self::tokenize_recursive(source, rest, tokens)
                 }
-                Err(e) => Err(e),
             },
-            c if c.is_ascii() => match parse_str_token(stream, c) {
+            '&' => match rest.strip_prefix('&') {
+                Some(rest) => {
+                    tokens.push(Token::KeywordAndAnd);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+                None => Err(TokenizeError::UnexpectedCharacter('&')),
+            },
+            '|' => match rest.strip_prefix('|') {
+                Some(rest) => {
+                    tokens.push(Token::KeywordOrOr);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+                None => {
+                    tokens.push(Token::KeywordPipe);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+            },
+            '<' => match rest.strip_prefix('=') {
+                Some(rest) => {
+                    tokens.push(Token::KeywordLessEqual);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+                None => {
+                    tokens.push(Token::KeywordLess);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+            },
+            '>' => match rest.strip_prefix('=') {
+                Some(rest) => {
+                    tokens.push(Token::KeywordGreaterEqual);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+                None => {
+                    tokens.push(Token::KeywordGreater);
+                    Self::tokenize_recursive(source, rest, tokens)
+                }
+            },
+            '"' => match parse_string_token(stream) {
                 Ok((token, rest)) => {
                     tokens.push(token);
-                    Self::tokenize_recursive(rest, tokens)
+                    Self::tokenize_recursive(source, rest, tokens)
                 }
                 Err(e) => Err(e),
             },
+            'r' if rest.trim_start_matches('#').starts_with('"') => {
+                match parse_raw_string_token(stream) {
+                    Ok((token, rest)) => {
+                        tokens.push(token);
+                        Self::tokenize_recursive(source, rest, tokens)
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            c if c.is_whitespace() => Self::tokenize_recursive(source, rest, tokens),
+            c if c.is_ascii_digit() => {
+                match parse_number_token(stream, source.len() - stream.len()) {
+                    Ok((token, rest)) => {
+                        tokens.push(token);
+                        Self::tokenize_recursive(source, rest, tokens)
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let (token, rest) = parse_str_token(stream, c);
+                tokens.push(token);
+                Self::tokenize_recursive(source, rest, tokens)
+            }
             c => Err(TokenizeError::UnexpectedCharacter(c)),
         }
     }
 }
 
-fn parse_int_token(stream: &str, first: char) -> Result<(Token, &str), TokenizeError> {
-    let mut numeric: String = first.to_string();
-    let mut consumed: usize = first.len_utf8();
-    let mut is_float: bool = false;
+// the text of the numeric literal at the start of `stream` and the rest of the stream;
+// letters and dots are taken in too, so that `12ab` or `1.2.3` are reported as a whole
+fn scan_number(stream: &str) -> (&str, &str) {
+    // only a radix prefix, since `0e-5` is a Float with a signed exponent
+    let radix: bool = matches!(
+        stream.get(..2),
+        Some("0x" | "0X" | "0o" | "0O" | "0b" | "0B")
+    );
+    let mut end: usize = 0;
+    let mut previous: char = ' ';
+    for (i, c) in stream.char_indices() {
+        let continues: bool = match c {
+            c if c.is_ascii_alphanumeric() || c == '_' => true,
+            // `1..3` is a range of two Int literals, not a Float
+            '.' => !stream[i + 1..].starts_with('.'),
+            '+' | '-' => !radix && matches!(previous, 'e' | 'E'),
+            _ => false,
+        };
+        if !continues {
+            break;
+        }
+        end = i + c.len_utf8();
+        previous = c;
+    }
+    (&stream[..end], &stream[end..])
+}
 
-    for (_, c) in stream[consumed..].char_indices() {
+// `offset` is where `stream` starts in the program
+fn parse_number_token(stream: &str, offset: usize) -> Result<(Token, &str), TokenizeError> {
+    let (literal, rest) = scan_number(stream);
+    match parse_number(literal) {
+        Ok(token) => Ok((token, rest)),
+        Err(error) => Err(TokenizeError::InvalidNumber {
+            literal: literal.to_string(),
+            offset,
+            error,
+        }),
+    }
+}
+
+// `0x1F`, `0o17`, `0b1010`, `1_000`, `1.5`, `.5`, `1.`, `1e-9` or `0.10d`
+fn parse_number(literal: &str) -> Result<Token, NumberError> {
+    let radix: Option<u32> = match literal.get(..2) {
+        Some("0x" | "0X") => Some(16),
+        Some("0o" | "0O") => Some(8),
+        Some("0b" | "0B") => Some(2),
+        _ => None,
+    };
+    if let Some(radix) = radix {
+        let digits: String = strip_separators(&literal[2..], radix)?;
+        let n: TinyInt = TinyInt::parse(&digits, radix).expect("digits are checked");
+        return Ok(Token::LiteralInt(n));
+    }
+
+    // a `d` suffix makes a Decimal, which keeps its digits exactly
+    let (number, decimal) = match literal.strip_suffix('d') {
+        Some(number) => (number, true),
+        None => (literal, false),
+    };
+    let (mantissa, exponent) = match number.find(['e', 'E']) {
+        Some(i) => (&number[..i], Some(&number[i + 1..])),
+        None => (number, None),
+    };
+    if decimal && exponent.is_some() {
+        return Err(NumberError::ExponentInDecimal);
+    }
+    if mantissa.matches('.').count() > 1 {
+        return Err(NumberError::MultipleDecimalPoints);
+    }
+    let (int_part, fraction) = match mantissa.split_once('.') {
+        Some((int_part, fraction)) => (int_part, Some(fraction)),
+        None => (mantissa, None),
+    };
+    let int_digits: String = match int_part {
+        // `.5` and `1.` leave one side of the point empty, but not both
+        "" if fraction.is_some_and(|f| !f.is_empty()) => String::new(),
+        _ => strip_separators(int_part, 10)?,
+    };
+    let fraction_digits: String = match fraction {
+        Some("") | None => String::new(),
+        Some(fraction) => strip_separators(fraction, 10)?,
+    };
+    let exponent_digits: Option<String> = match exponent {
+        Some(exponent) => {
+            let (sign, digits) = match exponent.strip_prefix(['+', '-']) {
+                Some(digits) => (&exponent[..1], digits),
+                None => ("", exponent),
+            };
+            Some(format!("{}{}", sign, strip_separators(digits, 10)?))
+        }
+        None => None,
+    };
+
+    if decimal {
+        let n: TinyDecimal =
+            TinyDecimal::parse(&int_digits, &fraction_digits).expect("digits are checked");
+        return Ok(Token::LiteralDecimal(n));
+    }
+    if fraction.is_none() && exponent_digits.is_none() {
+        let n: TinyInt = TinyInt::parse(&int_digits, 10).expect("digits are checked");
+        return Ok(Token::LiteralInt(n));
+    }
+    let float: String = format!(
+        "{}.{}e{}",
+        if int_digits.is_empty() {
+            "0"
+        } else {
+            &int_digits
+        },
+        if fraction_digits.is_empty() {
+            "0"
+        } else {
+            &fraction_digits
+        },
+        exponent_digits.as_deref().unwrap_or("0")
+    );
+    let n: f64 = float.parse::<f64>().expect("the float syntax is checked");
+    // a literal too small for f64 reads as zero, but one too large has no value
+    if n.is_infinite() {
+        return Err(NumberError::OutOfRange);
+    }
+    Ok(Token::LiteralFloat(n))
+}
+
+// the digits of one part of a literal without separators, which may only sit between digits
+fn strip_separators(part: &str, radix: u32) -> Result<String, NumberError> {
+    if part.is_empty() {
+        return Err(NumberError::MissingDigits);
+    }
+    let chars: Vec<char> = part.chars().collect();
+    let mut digits: String = String::new();
+    for (i, c) in chars.iter().enumerate() {
         match c {
-            '0'..='9' => {
-                numeric.push(c);
-                consumed += c.len_utf8();
-            }
-            '.' => {
-                is_float = true;
-                numeric.push(c);
-                consumed += c.len_utf8();
+            '_' => {
+                let between: bool = i > 0
+                    && chars[i - 1].is_digit(radix)
+                    && chars.get(i + 1).is_some_and(|next| next.is_digit(radix));
+                if !between {
+                    return Err(NumberError::MisplacedSeparator);
+                }
             }
-            _ => break,
+            c if c.is_digit(radix) => digits.push(*c),
+            c => return Err(NumberError::InvalidDigit(*c)),
         }
     }
+    Ok(digits)
+}
 
-    match is_float {
-        true => {
-            let n: f32 = numeric
-                .parse::<f32>()
-                .map_err(|_| TokenizeError::ParseIntError)?;
-            Ok((Token::LiteralFloat(n), &stream[consumed..]))
-        }
-        false => {
-            let n: i32 = numeric
-                .parse::<i32>()
-                .map_err(|_| TokenizeError::ParseIntError)?;
-            Ok((Token::LiteralInt(n), &stream[consumed..]))
+// `stream` starts at the opening quote; the literal may span several lines
+fn parse_string_token(stream: &str) -> Result<(Token, &str), TokenizeError> {
+    let mut literal: String = String::new();
+    let mut chars = stream.char_indices().skip(1);
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((Token::LiteralString(literal), &stream[i + 1..])),
+            '\\' => {
+                let (_, escaped) = chars.next().ok_or(TokenizeError::UnterminatedString)?;
+                match escaped {
+                    'n' => literal.push('\n'),
+                    't' => literal.push('\t'),
+                    'r' => literal.push('\r'),
+                    '0' => literal.push('\0'),
+                    '\\' | '"' | '\'' => literal.push(escaped),
+                    'u' => {
+                        let mut digits: String = String::new();
+                        let mut closed: bool = false;
+                        if let Some((_, '{')) = chars.next() {
+                            for (_, d) in chars.by_ref() {
+                                if d == '}' {
+                                    closed = true;
+                                    break;
+                                }
+                                digits.push(d);
+                            }
+                        }
+                        let unicode: Option<char> = match closed {
+                            true => u32::from_str_radix(&digits, 16)
+                                .ok()
+                                .and_then(char::from_u32),
+                            false => None,
+                        };
+                        match unicode {
+                            Some(u) => literal.push(u),
+                            None => {
+                                return Err(TokenizeError::InvalidEscape(format!(
+                                    "\\u{{{}}}",
+                                    digits
+                                )));
+                            }
+                        }
+                    }
+                    other => return Err(TokenizeError::InvalidEscape(format!("\\{}", other))),
+                }
+            }
+            c => literal.push(c),
         }
     }
+
+    Err(TokenizeError::UnterminatedString)
 }
 
-fn parse_str_token(stream: &str, first: char) -> Result<(Token, &str), TokenizeError> {
+// `stream` starts at the `r` of `r"..."` or `r#"..."#`; escapes are kept verbatim
+fn parse_raw_string_token(stream: &str) -> Result<(Token, &str), TokenizeError> {
+    let after_r: &str = &stream[1..];
+    let hashes: usize = after_r.len() - after_r.trim_start_matches('#').len();
+    let body: &str = &after_r[hashes + 1..];
+    let closing: String = format!("\"{}", "#".repeat(hashes));
+
+    match body.find(&closing) {
+        Some(end) => Ok((
+            Token::LiteralString(body[..end].to_string()),
+            &body[end + closing.len()..],
+        )),
+        None => Err(TokenizeError::UnterminatedString),
+    }
+}
+
+fn parse_str_token(stream: &str, first: char) -> (Token, &str) {
     let mut token_candidate: String = first.to_string();
     let mut consumed: usize = first.len_utf8();
 
     for (_, c) in stream[consumed..].char_indices() {
-        if c.is_ascii_alphanumeric() {
+        if c.is_ascii_alphanumeric() || c == '_' {
             token_candidate.push(c);
             consumed += c.len_utf8();
         } else {
@@ -126,9 +522,124 @@ fn parse_str_token(stream: &str, first: char) -> Result<(Token, &str), TokenizeE
         }
     }
 
-    match token_candidate.as_str() {
-        "true" => Ok((Token::LiteralBool(true), &stream[consumed..])),
-        "false" => Ok((Token::LiteralBool(false), &stream[consumed..])),
-        _ => Err(TokenizeError::UnexpectedKeyword(token_candidate)),
+    let token: Token = match token_candidate.as_str() {
+        "true" => Token::LiteralBool(true),
+        "false" => Token::LiteralBool(false),
+        "let" => Token::KeywordLet,
+        "in" => Token::KeywordIn,
+        "fn" => Token::KeywordFn,
+        "len" => Token::KeywordLen,
+        "with" => Token::KeywordWith,
+        "match" => Token::KeywordMatch,
+        "type" => Token::KeywordType,
+        "var" => Token::KeywordVar,
+        "while" => Token::KeywordWhile,
+        "break" => Token::KeywordBreak,
+        "continue" => Token::KeywordContinue,
+        "try" => Token::KeywordTry,
+        "catch" => Token::KeywordCatch,
+        "throw" => Token::KeywordThrow,
+        "as" => Token::KeywordAs,
+        _ => Token::Identifier(token_candidate),
+    };
+    (token, &stream[consumed..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_literal_error_has_offset() {
+        let result = Tokenizer::tokenize("let x = 12z");
+        assert!(matches!(
+            result,
+            Err(TokenizeError::InvalidNumber {
+                literal,
+                offset: 8,
+                error: NumberError::InvalidDigit('z'),
+            }) if literal == "12z"
+        ));
+    }
+
+    #[test]
+    fn float_literal_out_of_range() {
+        let result = Tokenizer::tokenize("1 + 1e400");
+        assert!(matches!(
+            result,
+            Err(TokenizeError::InvalidNumber {
+                offset: 4,
+                error: NumberError::OutOfRange,
+                ..
+            })
+        ));
+    }
+
+    fn token(literal: &str) -> Token {
+        match Tokenizer::tokenize(literal) {
+            Ok(tokens) if tokens.len() == 1 => tokens[0].clone(),
+            _ => panic!("not a single token: {}", literal),
+        }
+    }
+
+    #[test]
+    fn scientific_literals_with_zero_mantissa_and_signed_exponent() {
+        assert_eq!(token("0e-5"), Token::LiteralFloat(0.0));
+        assert_eq!(token("0E+3"), Token::LiteralFloat(0.0));
+        assert_eq!(token("0.5e-1"), Token::LiteralFloat(0.05));
+        assert_eq!(token("1e+3"), Token::LiteralFloat(1000.0));
+        assert_eq!(token("2E-2"), Token::LiteralFloat(0.02));
+    }
+
+    #[test]
+    fn radix_prefixes() {
+        assert_eq!(token("0x1F"), Token::LiteralInt(TinyInt::from(31)));
+        assert_eq!(token("0XfF"), Token::LiteralInt(TinyInt::from(255)));
+        assert_eq!(token("0o17"), Token::LiteralInt(TinyInt::from(15)));
+        assert_eq!(token("0b1010"), Token::LiteralInt(TinyInt::from(10)));
+        assert!(matches!(
+            Tokenizer::tokenize("0b102"),
+            Err(TokenizeError::InvalidNumber {
+                error: NumberError::InvalidDigit('2'),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn digit_separators() {
+        assert_eq!(
+            token("1_000_000"),
+            Token::LiteralInt(TinyInt::from(1_000_000))
+        );
+        assert_eq!(token("0xFF_FF"), Token::LiteralInt(TinyInt::from(0xFFFF)));
+        assert_eq!(token("1_000.000_1"), Token::LiteralFloat(1000.0001));
+        for literal in ["1_", "1__0", "0x_1", "1._5"] {
+            assert!(
+                matches!(
+                    Tokenizer::tokenize(literal),
+                    Err(TokenizeError::InvalidNumber {
+                        error: NumberError::MisplacedSeparator,
+                        ..
+                    })
+                ),
+                "{}",
+                literal
+            );
+        }
+    }
+
+    #[test]
+    fn leading_and_trailing_dot() {
+        assert_eq!(token(".5"), Token::LiteralFloat(0.5));
+        assert_eq!(token("1."), Token::LiteralFloat(1.0));
+        assert_eq!(
+            Tokenizer::tokenize("1..3").ok(),
+            Some(vec![
+                Token::LiteralInt(TinyInt::from(1)),
+                Token::KeywordDotDot,
+                Token::LiteralInt(TinyInt::from(3)),
+            ])
+        );
     }
 }
@@ -1,68 +1,2159 @@
-use crate::value_object::ast::{BinaryOperation, Expr, Stmt};
-use crate::value_object::tiny_type::TinyType;
+use crate::value_object::ast::{
+    BinaryOperation, Expr, Pattern, Stmt, TypeExpr, UnaryOperation, fn_group, pattern_to_string,
+    prelude,
+};
+use crate::value_object::tiny_int::TinyInt;
+use crate::value_object::tiny_type::{TinyType, type_to_string};
+use std::collections::{HashMap, HashSet};
 
 pub enum TypeCheckError {
-    CondMustBeBool,
-    TernaryReturnsTypeMustBeSame,
-    UndefinedOperation,
+    CondMustBeBool(TinyType),
+    TernaryReturnsTypeMustBeSame {
+        thn: TinyType,
+        els: TinyType,
+    },
+    UndefinedOperation(Vec<TinyType>),
+    UnboundVariable(String),
+    NotCallable(TinyType),
+    ArgumentCountMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    ArgumentTypeMismatch {
+        expected: TinyType,
+        actual: TinyType,
+    },
+    ReturnTypeMismatch {
+        expected: TinyType,
+        actual: TinyType,
+    },
+    ListElementTypeMismatch {
+        expected: TinyType,
+        actual: TinyType,
+    },
+    NotIndexable(TinyType),
+    IndexMustBeInt(TinyType),
+    NotARecord(TinyType),
+    MissingField {
+        field: String,
+        record: TinyType,
+    },
+    DuplicateField(String),
+    FieldTypeMismatch {
+        field: String,
+        expected: TinyType,
+        actual: TinyType,
+    },
+    UnknownRecordType(String),
+    UnknownType(String),
+    TypeArgumentCountMismatch {
+        name: String,
+        expected: usize,
+        actual: usize,
+    },
+    DuplicateType(String),
+    // two functions of one group would share a slot, so neither may shadow the other
+    DuplicateFunction(String),
+    DuplicateTypeParameter(String),
+    DuplicateConstructor(String),
+    UnknownConstructor(String),
+    ConstructorArityMismatch {
+        name: String,
+        expected: usize,
+        actual: usize,
+    },
+    PatternTypeMismatch {
+        expected: TinyType,
+        actual: TinyType,
+    },
+    MatchArmTypeMismatch {
+        expected: TinyType,
+        actual: TinyType,
+    },
+    DefaultOnNonOption(TinyType),
+    DefaultTypeMismatch {
+        expected: TinyType,
+        actual: TinyType,
+    },
+    NonExhaustiveMatch(TinyType),
+    NotATuple(TinyType),
+    AssignToImmutable(String),
+    CaptureOfLocalVar(String),
+    AssignTypeMismatch {
+        name: String,
+        expected: TinyType,
+        actual: TinyType,
+    },
+    OutsideLoop(String),
+    ThrowTypeMismatch(TinyType),
+    TryCatchTypeMismatch {
+        expected: TinyType,
+        actual: TinyType,
+    },
+    AnnotationMismatch {
+        declared: TinyType,
+        actual: TinyType,
+    },
+    InvalidConversion {
+        from: TinyType,
+        to: TinyType,
+    },
+    FunctionTypeMismatch {
+        name: String,
+        expected: TinyType,
+        actual: TinyType,
+    },
+    TupleArityMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    UnreachableArm(Pattern),
 }
 
 pub fn typecheck_error_to_message(e: TypeCheckError) -> String {
     match e {
-        TypeCheckError::TernaryReturnsTypeMustBeSame => {
-            "ternary return type must be same".to_string()
+        TypeCheckError::TernaryReturnsTypeMustBeSame { thn, els } => format!(
+            "ternary return type must be same: then: {}, else: {}",
+            type_to_string(thn),
+            type_to_string(els)
+        ),
+        TypeCheckError::CondMustBeBool(t) => format!(
+            "condition value must be bool: expected: Bool, actual: {}",
+            type_to_string(t)
+        ),
+        TypeCheckError::UndefinedOperation(operands) => format!(
+            "you are trying undefined operation on: {}",
+            operands
+                .into_iter()
+                .map(type_to_string)
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        TypeCheckError::UnboundVariable(name) => format!("variable is not defined: {}", name),
+        TypeCheckError::NotCallable(t) => {
+            format!("value of type {} can not be called", type_to_string(t))
+        }
+        TypeCheckError::ArgumentCountMismatch { expected, actual } => format!(
+            "function expects {} argument(s), but {} were given",
+            expected, actual
+        ),
+        TypeCheckError::ArgumentTypeMismatch { expected, actual } => format!(
+            "argument type mismatch: expected: {}, actual: {}",
+            type_to_string(expected),
+            type_to_string(actual)
+        ),
+        TypeCheckError::ReturnTypeMismatch { expected, actual } => format!(
+            "return type mismatch: expected: {}, actual: {}",
+            type_to_string(expected),
+            type_to_string(actual)
+        ),
+        TypeCheckError::ListElementTypeMismatch { expected, actual } => format!(
+            "list elements must have the same type: expected: {}, actual: {}",
+            type_to_string(expected),
+            type_to_string(actual)
+        ),
+        TypeCheckError::NotIndexable(t) => {
+            format!("value of type {} can not be indexed", type_to_string(t))
+        }
+        TypeCheckError::IndexMustBeInt(t) => {
+            format!("index must be Int, but got {}", type_to_string(t))
+        }
+        TypeCheckError::NotARecord(t) => {
+            format!("value of type {} is not a record", type_to_string(t))
+        }
+        TypeCheckError::MissingField { field, record } => {
+            format!("record {} has no field: {}", type_to_string(record), field)
+        }
+        TypeCheckError::DuplicateField(field) => format!("field is defined twice: {}", field),
+        TypeCheckError::FieldTypeMismatch {
+            field,
+            expected,
+            actual,
+        } => format!(
+            "field type mismatch: {}: expected: {}, actual: {}",
+            field,
+            type_to_string(expected),
+            type_to_string(actual)
+        ),
+        TypeCheckError::UnknownRecordType(field) => format!(
+            "type of the record must be known before accessing its field: {}",
+            field
+        ),
+        TypeCheckError::UnknownType(name) => format!("type is not defined: {}", name),
+        TypeCheckError::TypeArgumentCountMismatch {
+            name,
+            expected,
+            actual,
+        } => format!(
+            "type argument count mismatch: {}: expected: {}, actual: {}",
+            name, expected, actual
+        ),
+        TypeCheckError::DuplicateType(name) => format!("type is defined twice: {}", name),
+        TypeCheckError::DuplicateFunction(name) => format!(
+            "function is defined twice in one group of consecutive fn statements: {}",
+            name
+        ),
+        TypeCheckError::DuplicateTypeParameter(name) => {
+            format!("type parameter is defined twice: {}", name)
+        }
+        TypeCheckError::DuplicateConstructor(name) => {
+            format!("constructor is defined twice: {}", name)
+        }
+        TypeCheckError::UnknownConstructor(name) => {
+            format!("constructor is not defined: {}", name)
+        }
+        TypeCheckError::ConstructorArityMismatch {
+            name,
+            expected,
+            actual,
+        } => format!(
+            "constructor arity mismatch: {}: expected: {}, actual: {}",
+            name, expected, actual
+        ),
+        TypeCheckError::PatternTypeMismatch { expected, actual } => format!(
+            "pattern type mismatch: expected: {}, actual: {}",
+            type_to_string(expected),
+            type_to_string(actual)
+        ),
+        TypeCheckError::MatchArmTypeMismatch { expected, actual } => format!(
+            "match arms must have the same type: expected: {}, actual: {}",
+            type_to_string(expected),
+            type_to_string(actual)
+        ),
+        TypeCheckError::DefaultOnNonOption(t) => format!(
+            "left operand of ?? must be an Option: actual: {}",
+            type_to_string(t)
+        ),
+        TypeCheckError::DefaultTypeMismatch { expected, actual } => format!(
+            "fallback of ?? must have the type inside the Option: expected: {}, actual: {}",
+            type_to_string(expected),
+            type_to_string(actual)
+        ),
+        TypeCheckError::AssignToImmutable(name) => {
+            format!("can not assign to a variable declared with let: {}", name)
+        }
+        TypeCheckError::CaptureOfLocalVar(name) => format!(
+            "a function can not capture a var that is not declared at the top level: {}",
+            name
+        ),
+        TypeCheckError::AssignTypeMismatch {
+            name,
+            expected,
+            actual,
+        } => format!(
+            "assign type mismatch: {}: expected: {}, actual: {}",
+            name,
+            type_to_string(expected),
+            type_to_string(actual)
+        ),
+        TypeCheckError::AnnotationMismatch { declared, actual } => format!(
+            "type annotation mismatch: declared: {}, actual: {}",
+            type_to_string(declared),
+            type_to_string(actual)
+        ),
+        TypeCheckError::InvalidConversion { from, to } => format!(
+            "cannot convert {} to {}: only Int, Float and Decimal convert to each other",
+            type_to_string(from),
+            type_to_string(to)
+        ),
+        TypeCheckError::FunctionTypeMismatch {
+            name,
+            expected,
+            actual,
+        } => format!(
+            "function type mismatch: {}: used as: {}, defined as: {}",
+            name,
+            type_to_string(expected),
+            type_to_string(actual)
+        ),
+        TypeCheckError::OutsideLoop(keyword) => format!("{} outside of a loop", keyword),
+        TypeCheckError::ThrowTypeMismatch(actual) => format!(
+            "thrown value must be an error: expected: {}, actual: {}",
+            type_to_string(error_type()),
+            type_to_string(actual)
+        ),
+        TypeCheckError::TryCatchTypeMismatch { expected, actual } => format!(
+            "try and catch must have the same type: expected: {}, actual: {}",
+            type_to_string(expected),
+            type_to_string(actual)
+        ),
+        TypeCheckError::NotATuple(t) => {
+            format!("value of type {} is not a tuple", type_to_string(t))
+        }
+        TypeCheckError::TupleArityMismatch { expected, actual } => format!(
+            "tuple arity mismatch: expected: {}, actual: {}",
+            expected, actual
+        ),
+        TypeCheckError::NonExhaustiveMatch(t) => {
+            format!("match on {} is not exhaustive", type_to_string(t))
+        }
+        TypeCheckError::UnreachableArm(pattern) => {
+            format!("match arm is unreachable: {}", pattern_to_string(pattern))
+        }
+    }
+}
+
+// the values that `throw` raises and `catch` binds, including the errors raised by the VM
+fn error_type() -> TinyType {
+    TinyType::Record(vec![
+        (String::from("kind"), TinyType::String),
+        (String::from("message"), TinyType::String),
+    ])
+}
+
+// the types that arithmetic works on and `as` converts between
+fn is_numeric(t: &TinyType) -> bool {
+    matches!(t, TinyType::Int | TinyType::Float | TinyType::Decimal)
+}
+
+pub struct TypeChecker {
+    // innermost binding is the last one, so lookups search from the back
+    env: Vec<(String, Scheme)>,
+    // substitution for type variables; `TinyType::Var(id)` is bound once `subst[id]` is Some
+    subst: Vec<Option<TinyType>>,
+    // type variables that may only be bound to some types
+    bounds: HashMap<usize, Bound>,
+    // operations whose type depends on operands that are not known yet
+    arith: Vec<Arith>,
+    adts: HashMap<String, Adt>,
+    // constructor name to the sum type it builds and its tag
+    constructors: HashMap<String, (String, usize)>,
+    // type parameters in scope, which annotations may refer to by name
+    type_params: Vec<(String, TinyType)>,
+    // positions in `env` of the bindings declared with `var`
+    mutable: HashSet<usize>,
+    // positions in `env` of the `var`s declared by top-level statements, which are globals
+    global_vars: HashSet<usize>,
+    // length of `env` where the bindings of the innermost function start
+    function_base: usize,
+    // number of loops around the expression being checked, within the current function
+    loops: usize,
+}
+
+// a declared sum type; field types refer to the type parameters through the variables in `params`
+struct Adt {
+    params: Vec<usize>,
+    // constructors with their field types, in tag order
+    variants: Vec<(String, Vec<TinyType>)>,
+}
+
+// type of a binding; `vars` are quantified and replaced by fresh variables at each use, along
+// with their bounds and the operations over them
+#[derive(Clone)]
+struct Scheme {
+    vars: Vec<usize>,
+    bounds: Vec<(usize, Bound)>,
+    arith: Vec<Arith>,
+    ty: TinyType,
+}
+
+impl Scheme {
+    fn mono(ty: TinyType) -> Self {
+        Self {
+            vars: Vec::new(),
+            bounds: Vec::new(),
+            arith: Vec::new(),
+            ty,
         }
-        TypeCheckError::CondMustBeBool => "condition value must be bool".to_string(),
-        TypeCheckError::UndefinedOperation => "you are trying undefined operation".to_string(),
     }
 }
 
-pub struct TypeChecker {}
+// `left op right` for an arithmetic or comparison operator; `result` is the type of the
+// operation, decided once both operands are known since Int and Float mix into Float
+#[derive(Clone)]
+struct Arith {
+    left: TinyType,
+    right: TinyType,
+    result: TinyType,
+    bound: Bound,
+}
+
+// a pattern as seen by the exhaustiveness check; variables match anything just like `_`
+#[derive(Clone)]
+enum Space {
+    Any,
+    Ctor(Ctor, Vec<Space>),
+}
+
+#[derive(Clone, PartialEq)]
+enum Ctor {
+    Variant(usize),
+    Bool(bool),
+    Int(TinyInt),
+    String(String),
+}
+
+// restricts the types that an unbound type variable may later be bound to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Bound {
+    // Int, Float or Decimal, as required by arithmetic
+    Numeric,
+    // Int, Float, Decimal or String, as required by `+`
+    Addable,
+    // List or String, as required by `len`
+    Sized,
+}
+
+impl Bound {
+    fn admits(self, t: &TinyType) -> bool {
+        match self {
+            Bound::Numeric => is_numeric(t),
+            Bound::Addable => is_numeric(t) || *t == TinyType::String,
+            Bound::Sized => matches!(t, TinyType::List(_) | TinyType::String),
+        }
+    }
+
+    // the tighter of two bounds, since a variable must satisfy both; None when they share no
+    // type that a bound can express
+    fn meet(self, other: Bound) -> Option<Bound> {
+        match (self, other) {
+            (a, b) if a == b => Some(a),
+            (Bound::Numeric, Bound::Addable) | (Bound::Addable, Bound::Numeric) => {
+                Some(Bound::Numeric)
+            }
+            _ => None,
+        }
+    }
+}
 
 impl TypeChecker {
-    pub fn typecheck(ast: Stmt) -> Result<Option<TinyType>, TypeCheckError> {
-        match ast {
-            Stmt::Expr(expr) => Ok(Some(Self::typecheck_expr(expr)?)),
+    pub fn typecheck(ast: &mut [Stmt]) -> Result<Option<TinyType>, TypeCheckError> {
+        let mut checker = Self {
+            env: Vec::new(),
+            subst: Vec::new(),
+            bounds: HashMap::new(),
+            arith: Vec::new(),
+            adts: HashMap::new(),
+            constructors: HashMap::new(),
+            type_params: Vec::new(),
+            mutable: HashSet::new(),
+            global_vars: HashSet::new(),
+            function_base: 0,
+            loops: 0,
+        };
+        for stmt in prelude().iter_mut() {
+            checker.typecheck_stmt(stmt)?;
+        }
+        let mut result: Option<TinyType> = None;
+        let mut group_len: usize = 0;
+        for i in 0..ast.len() {
+            let group: Vec<String> = fn_group(ast, i);
+            if !group.is_empty() {
+                group_len = group.len();
+            }
+            if let Some(j) = (1..group.len()).find(|&j| group[..j].contains(&group[j])) {
+                return Err(TypeCheckError::DuplicateFunction(group[j].clone()));
+            }
+            for name in group {
+                let declared: TinyType = checker.fresh();
+                checker.env.push((name, Scheme::mono(declared)));
+            }
+            result = checker.typecheck_stmt(&mut ast[i])?;
+            // functions become polymorphic once every body of their group is checked
+            if matches!(ast[i], Stmt::Fn { .. }) && !matches!(ast.get(i + 1), Some(Stmt::Fn { .. }))
+            {
+                checker.generalize_last(group_len)?;
+            }
+        }
+        checker.solve_arith()?;
+        Ok(result.map(|t| checker.zonk(t)))
+    }
+
+    fn typecheck_stmt(&mut self, stmt: &mut Stmt) -> Result<Option<TinyType>, TypeCheckError> {
+        match stmt {
+            Stmt::Expr(expr) => Ok(Some(self.typecheck_expr(expr)?)),
+            Stmt::Let { name, value } => {
+                // top-level bindings stay visible to every later statement
+                let value: TinyType = self.typecheck_expr(value)?;
+                let scheme: Scheme = self.generalize(value)?;
+                self.env.push((name.clone(), scheme));
+                Ok(None)
+            }
+            Stmt::LetMut { name, value } => {
+                // a variable that can be assigned to keeps one type
+                let value: TinyType = self.typecheck_expr(value)?;
+                self.mutable.insert(self.env.len());
+                self.global_vars.insert(self.env.len());
+                self.env.push((name.clone(), Scheme::mono(value)));
+                Ok(None)
+            }
+            Stmt::LetTuple { names, value } => {
+                let value: TinyType = self.typecheck_expr(value)?;
+                let elements: Vec<TinyType> = self.destructure(&value, names.len())?;
+                let schemes: Vec<Scheme> = elements
+                    .into_iter()
+                    .map(|t| self.generalize(t))
+                    .collect::<Result<Vec<Scheme>, TypeCheckError>>()?;
+                self.env.extend(names.iter().cloned().zip(schemes));
+                Ok(None)
+            }
+            Stmt::Fn {
+                name,
+                type_params,
+                params,
+                param_types,
+                body,
+            } => {
+                let type_params: Vec<(String, TinyType)> = self.rigid_params(type_params)?;
+                self.type_params = type_params;
+                let checked = self.typecheck_fn(name, params, param_types, body);
+                self.type_params.clear();
+                checked?;
+                Ok(None)
+            }
+            Stmt::Type {
+                name,
+                params,
+                variants,
+            } => {
+                self.typecheck_type(name, params, variants)?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn typecheck_fn(
+        &mut self,
+        name: &str,
+        params: &[String],
+        param_types: &[Option<TypeExpr>],
+        body: &mut Expr,
+    ) -> Result<(), TypeCheckError> {
+        let param_types: Vec<TinyType> = self.param_types(param_types)?;
+        let ret: TinyType = self.fresh();
+        let fn_type = TinyType::Function {
+            params: param_types.clone(),
+            ret: Box::new(ret.clone()),
+        };
+
+        // declared with its group, so that calls checked so far may have shaped its type
+        let declared: TinyType = self.lookup(name)?;
+        self.unify(&declared, &fn_type)
+            .map_err(|_| TypeCheckError::FunctionTypeMismatch {
+                name: name.to_string(),
+                expected: self.zonk(declared.clone()),
+                actual: self.zonk(fn_type.clone()),
+            })?;
+        let depth: usize = self.env.len();
+        self.env.extend(
+            params
+                .iter()
+                .cloned()
+                .zip(param_types.into_iter().map(Scheme::mono)),
+        );
+        let body = self.typecheck_fn_body(body, depth);
+        self.env.truncate(depth);
+        let body: TinyType = body?;
+
+        self.unify(&ret, &body)
+            .map_err(|_| TypeCheckError::ReturnTypeMismatch {
+                expected: self.zonk(ret.clone()),
+                actual: self.zonk(body.clone()),
+            })
+    }
+
+    // type parameters of a generic function, named in the order they are declared
+    fn rigid_params(
+        &mut self,
+        names: &[String],
+    ) -> Result<Vec<(String, TinyType)>, TypeCheckError> {
+        let mut params: Vec<(String, TinyType)> = Vec::new();
+        for name in names {
+            if params.iter().any(|(n, _)| n == name) {
+                return Err(TypeCheckError::DuplicateTypeParameter(name.clone()));
+            }
+            let TinyType::Var(id) = self.fresh() else {
+                unreachable!()
+            };
+            params.push((
+                name.clone(),
+                TinyType::Param {
+                    name: name.clone(),
+                    id,
+                },
+            ));
+        }
+        Ok(params)
+    }
+
+    fn typecheck_type(
+        &mut self,
+        name: &str,
+        params: &[String],
+        variants: &[(String, Vec<TypeExpr>)],
+    ) -> Result<(), TypeCheckError> {
+        if self.adts.contains_key(name) {
+            return Err(TypeCheckError::DuplicateType(name.to_string()));
+        }
+        let mut vars: Vec<usize> = Vec::new();
+        for param in params {
+            if self.type_params.iter().any(|(n, _)| n == param) {
+                self.type_params.clear();
+                return Err(TypeCheckError::DuplicateTypeParameter(param.clone()));
+            }
+            let var: TinyType = self.fresh();
+            if let TinyType::Var(id) = var {
+                vars.push(id);
+            }
+            self.type_params.push((param.clone(), var));
+        }
+        // registered before the fields so that the type can refer to itself
+        self.adts.insert(
+            name.to_string(),
+            Adt {
+                params: vars.clone(),
+                variants: Vec::new(),
+            },
+        );
+        let resolved = self.typecheck_variants(name, &vars, variants);
+        self.type_params.clear();
+        self.adts.get_mut(name).unwrap().variants = resolved?;
+        Ok(())
+    }
+
+    fn typecheck_variants(
+        &mut self,
+        name: &str,
+        vars: &[usize],
+        variants: &[(String, Vec<TypeExpr>)],
+    ) -> Result<Vec<(String, Vec<TinyType>)>, TypeCheckError> {
+        let mut resolved: Vec<(String, Vec<TinyType>)> = Vec::new();
+        for (tag, (constructor, fields)) in variants.iter().enumerate() {
+            if self.constructors.contains_key(constructor) {
+                return Err(TypeCheckError::DuplicateConstructor(constructor.clone()));
+            }
+            self.constructors
+                .insert(constructor.clone(), (name.to_string(), tag));
+            let fields: Vec<TinyType> = fields
+                .iter()
+                .map(|field| self.resolve_type_expr(field))
+                .collect::<Result<Vec<TinyType>, TypeCheckError>>()?;
+
+            // constructors with fields are functions, the others are values;
+            // both are generic over the parameters of the type
+            let adt = TinyType::Adt {
+                name: name.to_string(),
+                args: vars.iter().map(|v| TinyType::Var(*v)).collect(),
+            };
+            let constructor_type: TinyType = if fields.is_empty() {
+                adt
+            } else {
+                TinyType::Function {
+                    params: fields.clone(),
+                    ret: Box::new(adt),
+                }
+            };
+            self.env.push((
+                constructor.clone(),
+                Scheme {
+                    vars: vars.to_vec(),
+                    bounds: Vec::new(),
+                    arith: Vec::new(),
+                    ty: constructor_type,
+                },
+            ));
+            resolved.push((constructor.clone(), fields));
+        }
+        Ok(resolved)
+    }
+
+    // field types of a constructor of `Adt { name, args }`
+    fn adt_fields(&self, name: &str, args: &[TinyType], tag: usize) -> Vec<TinyType> {
+        let adt: &Adt = &self.adts[name];
+        let mapping: HashMap<usize, TinyType> = adt
+            .params
+            .iter()
+            .copied()
+            .zip(args.iter().cloned())
+            .collect();
+        adt.variants[tag]
+            .1
+            .iter()
+            .map(|field| Self::substitute(field.clone(), &mapping))
+            .collect()
+    }
+
+    fn resolve_type_expr(&self, t: &TypeExpr) -> Result<TinyType, TypeCheckError> {
+        match t {
+            TypeExpr::Record(fields) => {
+                let mut types: Vec<(String, TinyType)> = Vec::new();
+                for (name, field) in fields {
+                    if types.iter().any(|(n, _)| n == name) {
+                        return Err(TypeCheckError::DuplicateField(name.clone()));
+                    }
+                    types.push((name.clone(), self.resolve_type_expr(field)?));
+                }
+                types.sort_by(|(a, _), (b, _)| a.cmp(b));
+                Ok(TinyType::Record(types))
+            }
+            TypeExpr::Tuple(elements) => Ok(TinyType::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.resolve_type_expr(element))
+                    .collect::<Result<Vec<TinyType>, TypeCheckError>>()?,
+            )),
+            TypeExpr::Function { params, ret } => Ok(TinyType::Function {
+                params: params
+                    .iter()
+                    .map(|param| self.resolve_type_expr(param))
+                    .collect::<Result<Vec<TinyType>, TypeCheckError>>()?,
+                ret: Box::new(self.resolve_type_expr(ret)?),
+            }),
+            TypeExpr::Named { name, args } => {
+                let param: Option<TinyType> = self
+                    .type_params
+                    .iter()
+                    .rev()
+                    .find(|(n, _)| n == name)
+                    .map(|(_, t)| t.clone());
+                // the number of arguments a type takes, as its kind
+                let expected: usize = match name.as_str() {
+                    _ if param.is_some() => 0,
+                    "List" => 1,
+                    "Int" | "Float" | "Decimal" | "Bool" | "String" => 0,
+                    _ if self.adts.contains_key(name) => self.adts[name].params.len(),
+                    _ => return Err(TypeCheckError::UnknownType(name.clone())),
+                };
+                if args.len() != expected {
+                    return Err(TypeCheckError::TypeArgumentCountMismatch {
+                        name: name.clone(),
+                        expected,
+                        actual: args.len(),
+                    });
+                }
+                if let Some(param) = param {
+                    return Ok(param);
+                }
+                match name.as_str() {
+                    "Int" => Ok(TinyType::Int),
+                    "Float" => Ok(TinyType::Float),
+                    "Decimal" => Ok(TinyType::Decimal),
+                    "Bool" => Ok(TinyType::Bool),
+                    "String" => Ok(TinyType::String),
+                    "List" => Ok(TinyType::List(Box::new(self.resolve_type_expr(&args[0])?))),
+                    _ => Ok(TinyType::Adt {
+                        name: name.clone(),
+                        args: args
+                            .iter()
+                            .map(|arg| self.resolve_type_expr(arg))
+                            .collect::<Result<Vec<TinyType>, TypeCheckError>>()?,
+                    }),
+                }
+            }
         }
     }
 
-    fn typecheck_expr(expr: Expr) -> Result<TinyType, TypeCheckError> {
+    fn typecheck_expr(&mut self, expr: &mut Expr) -> Result<TinyType, TypeCheckError> {
         match expr {
             Expr::Bool(_) => Ok(TinyType::Bool),
             Expr::Float(_) => Ok(TinyType::Float),
+            Expr::Decimal(_) => Ok(TinyType::Decimal),
+            Expr::String(_) => Ok(TinyType::String),
             Expr::Int(_) => Ok(TinyType::Int),
-            Expr::If { cond, thn, els } => Ok(Self::typecheck_if(*cond, *thn, *els)?),
-            Expr::BinOp(op) => Ok(Self::typecheck_binop(*op)?),
+            Expr::If { cond, thn, els } => Ok(self.typecheck_if(cond, thn, els)?),
+            Expr::Default { option, fallback } => self.typecheck_default(option, fallback),
+            Expr::Let { name, value, body } => Ok(self.typecheck_let(name, value, body)?),
+            Expr::LetTuple { names, value, body } => {
+                let value: TinyType = self.typecheck_expr(value)?;
+                let elements: Vec<TinyType> = self.destructure(&value, names.len())?;
+                let schemes: Vec<Scheme> = elements
+                    .into_iter()
+                    .map(|t| self.generalize(t))
+                    .collect::<Result<Vec<Scheme>, TypeCheckError>>()?;
+                let depth: usize = self.env.len();
+                self.env.extend(names.iter().cloned().zip(schemes));
+                let body = self.typecheck_expr(body);
+                self.env.truncate(depth);
+                body
+            }
+            Expr::LetMut { name, value, body } => {
+                let value: TinyType = self.typecheck_expr(value)?;
+                let position: usize = self.env.len();
+                self.mutable.insert(position);
+                self.env.push((name.clone(), Scheme::mono(value)));
+                let body = self.typecheck_expr(body);
+                self.env.pop();
+                self.mutable.remove(&position);
+                body
+            }
+            Expr::Assign { name, value } => Ok(self.typecheck_assign(name, value)?),
+            Expr::Block(items) => {
+                let mut last: TinyType = TinyType::Tuple(Vec::new());
+                for item in items.iter_mut() {
+                    last = self.typecheck_expr(item)?;
+                }
+                Ok(last)
+            }
+            Expr::While { cond, body } => {
+                let cond: TinyType = self.typecheck_expr(cond)?;
+                self.unify(&cond, &TinyType::Bool)
+                    .map_err(|_| TypeCheckError::CondMustBeBool(self.zonk(cond.clone())))?;
+                self.loops += 1;
+                let body = self.typecheck_expr(body);
+                self.loops -= 1;
+                body?;
+                Ok(TinyType::Tuple(Vec::new()))
+            }
+            // jumping away never produces a value, so it fits wherever it is used
+            Expr::Break | Expr::Continue if self.loops > 0 => Ok(self.fresh()),
+            Expr::Break => Err(TypeCheckError::OutsideLoop(String::from("break"))),
+            // like `break`, a throw never produces a value
+            Expr::Throw(value) => {
+                let value: TinyType = self.typecheck_expr(value)?;
+                self.unify(&value, &error_type())
+                    .map_err(|_| TypeCheckError::ThrowTypeMismatch(self.zonk(value.clone())))?;
+                Ok(self.fresh())
+            }
+            Expr::Try {
+                body,
+                name,
+                handler,
+            } => self.typecheck_try(body, name, handler),
+            Expr::Continue => Err(TypeCheckError::OutsideLoop(String::from("continue"))),
+            Expr::Tuple(elements) => Ok(TinyType::Tuple(
+                elements
+                    .iter_mut()
+                    .map(|e| self.typecheck_expr(e))
+                    .collect::<Result<Vec<TinyType>, TypeCheckError>>()?,
+            )),
+            Expr::Var(name) => {
+                self.check_capture(name)?;
+                self.lookup(name)
+            }
+            Expr::Match { scrutinee, arms } => Ok(self.typecheck_match(scrutinee, arms)?),
+            Expr::Lambda {
+                params,
+                param_types,
+                body,
+            } => Ok(self.typecheck_lambda(params, param_types, body)?),
+            Expr::Annotated { expr, ty } => {
+                let declared: TinyType = self.resolve_type_expr(ty)?;
+                let actual: TinyType = self.typecheck_expr(expr)?;
+                if !self.widen(expr, &declared, &actual) {
+                    self.unify(&declared, &actual).map_err(|_| {
+                        TypeCheckError::AnnotationMismatch {
+                            declared: self.zonk(declared.clone()),
+                            actual: self.zonk(actual.clone()),
+                        }
+                    })?;
+                }
+                Ok(declared)
+            }
+            Expr::Convert { expr, target } => self.typecheck_convert(expr, target),
+            Expr::Call { callee, args } => Ok(self.typecheck_call(callee, args)?),
+            Expr::List(elements) => Ok(self.typecheck_list(elements)?),
+            Expr::Index { expr, index } => {
+                let element: TinyType = self.typecheck_list_operand(expr)?;
+                self.typecheck_index(index)?;
+                Ok(element)
+            }
+            Expr::Slice { expr, start, end } => {
+                let element: TinyType = self.typecheck_list_operand(expr)?;
+                self.typecheck_index(start)?;
+                self.typecheck_index(end)?;
+                Ok(TinyType::List(Box::new(element)))
+            }
+            Expr::Len(expr) => {
+                let expr: TinyType = self.typecheck_expr(expr)?;
+                match self.resolve(&expr) {
+                    TinyType::String | TinyType::List(_) => Ok(TinyType::Int),
+                    TinyType::Var(id) => {
+                        self.constrain(id, Bound::Sized)
+                            .map_err(|_| self.undefined_operation(&[&expr]))?;
+                        Ok(TinyType::Int)
+                    }
+                    _ => Err(self.undefined_operation(&[&expr])),
+                }
+            }
+            Expr::Record(fields) => Ok(self.typecheck_record(fields)?),
+            Expr::Field {
+                expr,
+                field,
+                offset,
+            } => {
+                let record: TinyType = self.typecheck_expr(expr)?;
+                let (t, o) = self.lookup_field(&record, field)?;
+                *offset = Some(o);
+                Ok(t)
+            }
+            Expr::RecordUpdate {
+                record,
+                fields,
+                offsets,
+            } => {
+                let record: TinyType = self.typecheck_expr(record)?;
+                *offsets = Some(self.typecheck_record_update(&record, fields)?);
+                Ok(record)
+            }
+            Expr::BinOp(op) => Ok(self.typecheck_binop(op)?),
+            Expr::UnaryOp(op) => Ok(self.typecheck_unaryop(op)?),
         }
     }
 
-    fn typecheck_if(cond: Expr, thn: Expr, els: Expr) -> Result<TinyType, TypeCheckError> {
-        let cond: TinyType = Self::typecheck_expr(cond)?;
-        if cond != TinyType::Bool {
-            return Err(TypeCheckError::CondMustBeBool);
+    fn typecheck_try(
+        &mut self,
+        body: &mut Expr,
+        name: &str,
+        handler: &mut Expr,
+    ) -> Result<TinyType, TypeCheckError> {
+        let body: TinyType = self.typecheck_expr(body)?;
+        self.env
+            .push((name.to_string(), Scheme::mono(error_type())));
+        let handler = self.typecheck_expr(handler);
+        self.env.pop();
+        let handler: TinyType = handler?;
+        self.unify(&body, &handler)
+            .map_err(|_| TypeCheckError::TryCatchTypeMismatch {
+                expected: self.zonk(body.clone()),
+                actual: self.zonk(handler.clone()),
+            })?;
+        Ok(body)
+    }
+
+    fn typecheck_if(
+        &mut self,
+        cond: &mut Expr,
+        thn: &mut Expr,
+        els: &mut Expr,
+    ) -> Result<TinyType, TypeCheckError> {
+        let cond: TinyType = self.typecheck_expr(cond)?;
+        self.unify(&cond, &TinyType::Bool)
+            .map_err(|_| TypeCheckError::CondMustBeBool(self.zonk(cond.clone())))?;
+        let thn: TinyType = self.typecheck_expr(thn)?;
+        let els: TinyType = self.typecheck_expr(els)?;
+        self.unify(&thn, &els)
+            .map_err(|_| TypeCheckError::TernaryReturnsTypeMustBeSame {
+                thn: self.zonk(thn.clone()),
+                els: self.zonk(els.clone()),
+            })?;
+
+        Ok(thn)
+    }
+
+    fn typecheck_default(
+        &mut self,
+        option: &mut Expr,
+        fallback: &mut Expr,
+    ) -> Result<TinyType, TypeCheckError> {
+        let option: TinyType = self.typecheck_expr(option)?;
+        let element: TinyType = self.fresh();
+        let expected = TinyType::Adt {
+            name: String::from("Option"),
+            args: vec![element.clone()],
+        };
+        self.unify(&option, &expected)
+            .map_err(|_| TypeCheckError::DefaultOnNonOption(self.zonk(option.clone())))?;
+        let fallback: TinyType = self.typecheck_expr(fallback)?;
+        self.unify(&element, &fallback)
+            .map_err(|_| TypeCheckError::DefaultTypeMismatch {
+                expected: self.zonk(element.clone()),
+                actual: self.zonk(fallback.clone()),
+            })?;
+        Ok(element)
+    }
+
+    fn typecheck_let(
+        &mut self,
+        name: &str,
+        value: &mut Expr,
+        body: &mut Expr,
+    ) -> Result<TinyType, TypeCheckError> {
+        let value: TinyType = self.typecheck_expr(value)?;
+        let scheme: Scheme = self.generalize(value)?;
+        self.env.push((name.to_string(), scheme));
+        let body = self.typecheck_expr(body);
+        self.env.pop();
+        body
+    }
+
+    fn typecheck_match(
+        &mut self,
+        scrutinee: &mut Expr,
+        arms: &mut [(Pattern, Expr)],
+    ) -> Result<TinyType, TypeCheckError> {
+        let scrutinee: TinyType = self.typecheck_expr(scrutinee)?;
+        let result: TinyType = self.fresh();
+        for (pattern, body) in arms.iter_mut() {
+            let depth: usize = self.env.len();
+            let body = self
+                .typecheck_pattern(pattern, &scrutinee)
+                .and_then(|_| self.typecheck_expr(body));
+            self.env.truncate(depth);
+            let body: TinyType = body?;
+            self.unify(&result, &body)
+                .map_err(|_| TypeCheckError::MatchArmTypeMismatch {
+                    expected: self.zonk(result.clone()),
+                    actual: self.zonk(body.clone()),
+                })?;
         }
-        let thn: TinyType = Self::typecheck_expr(thn)?;
-        let els: TinyType = Self::typecheck_expr(els)?;
-        if thn != els {
-            return Err(TypeCheckError::TernaryReturnsTypeMustBeSame);
+
+        let scrutinee: TinyType = self.zonk(scrutinee);
+        let mut rows: Vec<Vec<Space>> = Vec::new();
+        for (pattern, _) in arms.iter() {
+            let row: Vec<Space> = vec![self.space(pattern)];
+            if !self.useful(&rows, &row, std::slice::from_ref(&scrutinee)) {
+                return Err(TypeCheckError::UnreachableArm(pattern.clone()));
+            }
+            rows.push(row);
         }
+        if self.useful(&rows, &[Space::Any], std::slice::from_ref(&scrutinee)) {
+            return Err(TypeCheckError::NonExhaustiveMatch(scrutinee));
+        }
+        Ok(result)
+    }
 
-        Ok(thn)
+    // binds the variables of the pattern in the environment
+    fn typecheck_pattern(
+        &mut self,
+        pattern: &Pattern,
+        expected: &TinyType,
+    ) -> Result<(), TypeCheckError> {
+        let actual: TinyType = match pattern {
+            Pattern::Wildcard => return Ok(()),
+            Pattern::Var(name) => {
+                self.env
+                    .push((name.clone(), Scheme::mono(expected.clone())));
+                return Ok(());
+            }
+            Pattern::Int(_) => TinyType::Int,
+            Pattern::Bool(_) => TinyType::Bool,
+            Pattern::String(_) => TinyType::String,
+            Pattern::Constructor { name, args } => {
+                let (adt, tag) = self
+                    .constructors
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| TypeCheckError::UnknownConstructor(name.clone()))?;
+                let type_args: Vec<TinyType> = (0..self.adts[&adt].params.len())
+                    .map(|_| self.fresh())
+                    .collect();
+                let fields: Vec<TinyType> = self.adt_fields(&adt, &type_args, tag);
+                if fields.len() != args.len() {
+                    return Err(TypeCheckError::ConstructorArityMismatch {
+                        name: name.clone(),
+                        expected: fields.len(),
+                        actual: args.len(),
+                    });
+                }
+                for (arg, field) in args.iter().zip(fields.iter()) {
+                    self.typecheck_pattern(arg, field)?;
+                }
+                TinyType::Adt {
+                    name: adt,
+                    args: type_args,
+                }
+            }
+        };
+        self.unify(expected, &actual)
+            .map_err(|_| TypeCheckError::PatternTypeMismatch {
+                expected: self.zonk(expected.clone()),
+                actual,
+            })
+    }
+
+    fn typecheck_assign(
+        &mut self,
+        name: &str,
+        value: &mut Expr,
+    ) -> Result<TinyType, TypeCheckError> {
+        let position: usize = self
+            .env
+            .iter()
+            .rposition(|(n, _)| n == name)
+            .ok_or_else(|| TypeCheckError::UnboundVariable(name.to_string()))?;
+        if !self.mutable.contains(&position) {
+            return Err(TypeCheckError::AssignToImmutable(name.to_string()));
+        }
+        self.check_capture(name)?;
+        let expected: TinyType = self.env[position].1.ty.clone();
+        let actual: TinyType = self.typecheck_expr(value)?;
+        self.unify(&expected, &actual)
+            .map_err(|_| TypeCheckError::AssignTypeMismatch {
+                name: name.to_string(),
+                expected: self.zonk(expected.clone()),
+                actual: self.zonk(actual.clone()),
+            })?;
+        Ok(TinyType::Tuple(Vec::new()))
+    }
+
+    // unannotated parameters start out as fresh type variables
+    fn param_types(
+        &mut self,
+        annotations: &[Option<TypeExpr>],
+    ) -> Result<Vec<TinyType>, TypeCheckError> {
+        annotations
+            .iter()
+            .map(|annotation| match annotation {
+                Some(t) => self.resolve_type_expr(t),
+                None => Ok(self.fresh()),
+            })
+            .collect()
+    }
+
+    // loops around a function do not reach into its body; `base` is where its bindings start
+    fn typecheck_fn_body(
+        &mut self,
+        body: &mut Expr,
+        base: usize,
+    ) -> Result<TinyType, TypeCheckError> {
+        let loops: usize = std::mem::take(&mut self.loops);
+        let outer: usize = std::mem::replace(&mut self.function_base, base);
+        let body = self.typecheck_expr(body);
+        self.loops = loops;
+        self.function_base = outer;
+        body
     }
 
-    fn typecheck_binop(op: BinaryOperation) -> Result<TinyType, TypeCheckError> {
+    // a closure copies the locals it captures when it is created, so it would miss later
+    // assignments to a local `var`; a top-level `var` is a global that every function shares
+    fn check_capture(&self, name: &str) -> Result<(), TypeCheckError> {
+        match self.env.iter().rposition(|(n, _)| n == name) {
+            Some(position)
+                if position < self.function_base
+                    && self.mutable.contains(&position)
+                    && !self.global_vars.contains(&position) =>
+            {
+                Err(TypeCheckError::CaptureOfLocalVar(name.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // returns the element types of a tuple bound to `arity` names
+    fn destructure(
+        &mut self,
+        value: &TinyType,
+        arity: usize,
+    ) -> Result<Vec<TinyType>, TypeCheckError> {
+        match self.resolve(value) {
+            TinyType::Tuple(elements) if elements.len() == arity => Ok(elements),
+            TinyType::Tuple(elements) => Err(TypeCheckError::TupleArityMismatch {
+                expected: arity,
+                actual: elements.len(),
+            }),
+            TinyType::Var(_) => {
+                let elements: Vec<TinyType> = (0..arity).map(|_| self.fresh()).collect();
+                self.unify(value, &TinyType::Tuple(elements.clone()))
+                    .map_err(|_| TypeCheckError::NotATuple(self.zonk(value.clone())))?;
+                Ok(elements)
+            }
+            other => Err(TypeCheckError::NotATuple(self.zonk(other))),
+        }
+    }
+
+    fn typecheck_lambda(
+        &mut self,
+        params: &[String],
+        param_types: &[Option<TypeExpr>],
+        body: &mut Expr,
+    ) -> Result<TinyType, TypeCheckError> {
+        let param_types: Vec<TinyType> = self.param_types(param_types)?;
+        let depth: usize = self.env.len();
+        self.env.extend(
+            params
+                .iter()
+                .cloned()
+                .zip(param_types.iter().cloned().map(Scheme::mono)),
+        );
+        let body = self.typecheck_fn_body(body, depth);
+        self.env.truncate(depth);
+
+        Ok(TinyType::Function {
+            params: param_types,
+            ret: Box::new(body?),
+        })
+    }
+
+    // a list that mixes Int and Float elements is a List<Float>
+    fn typecheck_list(&mut self, elements: &mut [Expr]) -> Result<TinyType, TypeCheckError> {
+        let types: Vec<TinyType> = elements
+            .iter_mut()
+            .map(|e| self.typecheck_expr(e))
+            .collect::<Result<Vec<TinyType>, TypeCheckError>>()?;
+        let element: TinyType = if types.iter().any(|t| self.resolve(t) == TinyType::Float) {
+            TinyType::Float
+        } else {
+            self.fresh()
+        };
+        for (e, actual) in elements.iter_mut().zip(types.iter()) {
+            if self.widen(e, &element, actual) {
+                continue;
+            }
+            self.unify(&element, actual)
+                .map_err(|_| TypeCheckError::ListElementTypeMismatch {
+                    expected: self.zonk(element.clone()),
+                    actual: self.zonk(actual.clone()),
+                })?;
+        }
+        Ok(TinyType::List(Box::new(element)))
+    }
+
+    // an Int stands wherever a Float is expected, and so does a list literal of Ints where a
+    // List<Float> is; returns whether `expr` was turned into a conversion to Float
+    fn widen(&self, expr: &mut Expr, expected: &TinyType, actual: &TinyType) -> bool {
+        match (self.resolve(expected), self.resolve(actual)) {
+            (TinyType::Float, TinyType::Int) => {
+                let int: Expr = std::mem::replace(expr, Expr::Bool(false));
+                *expr = Expr::Convert {
+                    expr: Box::new(int),
+                    target: TypeExpr::Named {
+                        name: String::from("Float"),
+                        args: Vec::new(),
+                    },
+                };
+                true
+            }
+            (TinyType::List(expected), TinyType::List(actual)) => match expr {
+                Expr::List(elements) if !elements.is_empty() => elements
+                    .iter_mut()
+                    .all(|e| self.widen(e, &expected, &actual)),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    // returns the element type of the indexed list
+    fn typecheck_list_operand(&mut self, expr: &mut Expr) -> Result<TinyType, TypeCheckError> {
+        let expr: TinyType = self.typecheck_expr(expr)?;
+        let element: TinyType = self.fresh();
+        self.unify(&expr, &TinyType::List(Box::new(element.clone())))
+            .map_err(|_| TypeCheckError::NotIndexable(self.zonk(expr.clone())))?;
+        Ok(element)
+    }
+
+    fn typecheck_index(&mut self, index: &mut Expr) -> Result<(), TypeCheckError> {
+        let index: TinyType = self.typecheck_expr(index)?;
+        self.unify(&index, &TinyType::Int)
+            .map_err(|_| TypeCheckError::IndexMustBeInt(self.zonk(index.clone())))
+    }
+
+    fn typecheck_record(
+        &mut self,
+        fields: &mut [(String, Expr)],
+    ) -> Result<TinyType, TypeCheckError> {
+        let mut types: Vec<(String, TinyType)> = Vec::new();
+        for (name, value) in fields.iter_mut() {
+            if types.iter().any(|(n, _)| n == name) {
+                return Err(TypeCheckError::DuplicateField(name.clone()));
+            }
+            let value: TinyType = self.typecheck_expr(value)?;
+            types.push((name.clone(), value));
+        }
+        types.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(TinyType::Record(types))
+    }
+
+    // returns the offset of each updated field
+    fn typecheck_record_update(
+        &mut self,
+        record: &TinyType,
+        fields: &mut [(String, Expr)],
+    ) -> Result<Vec<usize>, TypeCheckError> {
+        let mut offsets: Vec<usize> = Vec::new();
+        for (name, value) in fields.iter_mut() {
+            let (expected, offset) = self.lookup_field(record, name)?;
+            if offsets.contains(&offset) {
+                return Err(TypeCheckError::DuplicateField(name.clone()));
+            }
+            let actual: TinyType = self.typecheck_expr(value)?;
+            self.unify(&expected, &actual)
+                .map_err(|_| TypeCheckError::FieldTypeMismatch {
+                    field: name.clone(),
+                    expected: self.zonk(expected.clone()),
+                    actual: self.zonk(actual.clone()),
+                })?;
+            offsets.push(offset);
+        }
+        Ok(offsets)
+    }
+
+    // returns the type of the field and its offset in the record
+    fn lookup_field(
+        &self,
+        record: &TinyType,
+        field: &str,
+    ) -> Result<(TinyType, usize), TypeCheckError> {
+        match self.resolve(record) {
+            TinyType::Record(fields) => fields
+                .iter()
+                .position(|(name, _)| name == field)
+                .map(|offset| (fields[offset].1.clone(), offset))
+                .ok_or_else(|| TypeCheckError::MissingField {
+                    field: field.to_string(),
+                    record: self.zonk(record.clone()),
+                }),
+            TinyType::Var(_) => Err(TypeCheckError::UnknownRecordType(field.to_string())),
+            other => Err(TypeCheckError::NotARecord(self.zonk(other))),
+        }
+    }
+
+    fn lookup(&mut self, name: &str) -> Result<TinyType, TypeCheckError> {
+        let scheme: Scheme = self
+            .env
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, scheme)| scheme.clone())
+            .ok_or_else(|| TypeCheckError::UnboundVariable(name.to_string()))?;
+        Ok(self.instantiate(&scheme))
+    }
+
+    fn typecheck_call(
+        &mut self,
+        callee: &mut Expr,
+        arg_exprs: &mut [Expr],
+    ) -> Result<TinyType, TypeCheckError> {
+        let callee: TinyType = self.typecheck_expr(callee)?;
+        let args: Vec<TinyType> = arg_exprs
+            .iter_mut()
+            .map(|arg| self.typecheck_expr(arg))
+            .collect::<Result<Vec<TinyType>, TypeCheckError>>()?;
+
+        let (params, ret) = match self.resolve(&callee) {
+            TinyType::Function { params, ret } => (params, *ret),
+            TinyType::Var(_) => {
+                let params: Vec<TinyType> = args.iter().map(|_| self.fresh()).collect();
+                let ret: TinyType = self.fresh();
+                let fn_type = TinyType::Function {
+                    params: params.clone(),
+                    ret: Box::new(ret.clone()),
+                };
+                self.unify(&callee, &fn_type)
+                    .map_err(|_| TypeCheckError::NotCallable(self.zonk(callee)))?;
+                (params, ret)
+            }
+            other => return Err(TypeCheckError::NotCallable(self.zonk(other))),
+        };
+
+        if params.len() != args.len() {
+            return Err(TypeCheckError::ArgumentCountMismatch {
+                expected: params.len(),
+                actual: args.len(),
+            });
+        }
+        for ((param, arg), expr) in params.iter().zip(args.iter()).zip(arg_exprs.iter_mut()) {
+            if self.widen(expr, param, arg) {
+                continue;
+            }
+            self.unify(param, arg)
+                .map_err(|_| TypeCheckError::ArgumentTypeMismatch {
+                    expected: self.zonk(param.clone()),
+                    actual: self.zonk(arg.clone()),
+                })?;
+        }
+        // the arguments may decide the operations in the body of a generic callee
+        self.solve_arith()?;
+
+        Ok(ret)
+    }
+
+    fn typecheck_binop(&mut self, op: &mut BinaryOperation) -> Result<TinyType, TypeCheckError> {
         match op {
             BinaryOperation::Add { left, right } => {
-                let left: TinyType = Self::typecheck_expr(*left)?;
-                let right: TinyType = Self::typecheck_expr(*right)?;
-                match (left, right) {
-                    (TinyType::Float, TinyType::Float)
-                    | (TinyType::Int, TinyType::Float)
-                    | (TinyType::Float, TinyType::Int) => Ok(TinyType::Float),
-                    (TinyType::Int, TinyType::Int) => Ok(TinyType::Int),
-                    _ => Err(TypeCheckError::UndefinedOperation),
+                let left: TinyType = self.typecheck_expr(left)?;
+                let right: TinyType = self.typecheck_expr(right)?;
+                self.typecheck_add(left, right)
+            }
+            BinaryOperation::Sub { left, right }
+            | BinaryOperation::Mul { left, right }
+            | BinaryOperation::Div { left, right }
+            | BinaryOperation::Mod { left, right } => {
+                let left: TinyType = self.typecheck_expr(left)?;
+                let right: TinyType = self.typecheck_expr(right)?;
+                self.typecheck_numeric(left, right, Bound::Numeric)
+            }
+            BinaryOperation::Lt { left, right }
+            | BinaryOperation::Le { left, right }
+            | BinaryOperation::Gt { left, right }
+            | BinaryOperation::Ge { left, right } => {
+                let left: TinyType = self.typecheck_expr(left)?;
+                let right: TinyType = self.typecheck_expr(right)?;
+                self.typecheck_numeric(left, right, Bound::Numeric)?;
+                Ok(TinyType::Bool)
+            }
+            BinaryOperation::Eq { left, right } | BinaryOperation::Ne { left, right } => {
+                let left: TinyType = self.typecheck_expr(left)?;
+                let right: TinyType = self.typecheck_expr(right)?;
+                self.typecheck_equality(left, right)?;
+                Ok(TinyType::Bool)
+            }
+            BinaryOperation::And { left, right } | BinaryOperation::Or { left, right } => {
+                let left: TinyType = self.typecheck_expr(left)?;
+                let right: TinyType = self.typecheck_expr(right)?;
+                self.typecheck_bool(left)?;
+                self.typecheck_bool(right)
+            }
+        }
+    }
+
+    fn typecheck_unaryop(&mut self, op: &mut UnaryOperation) -> Result<TinyType, TypeCheckError> {
+        match op {
+            UnaryOperation::Not { expr } => {
+                let expr: TinyType = self.typecheck_expr(expr)?;
+                self.typecheck_bool(expr)
+            }
+            UnaryOperation::Neg { expr } => {
+                let expr: TinyType = self.typecheck_expr(expr)?;
+                match self.resolve(&expr) {
+                    TinyType::Int | TinyType::Float | TinyType::Decimal => Ok(expr),
+                    TinyType::Var(id) => {
+                        self.constrain(id, Bound::Numeric)
+                            .map_err(|_| self.undefined_operation(&[&expr]))?;
+                        Ok(expr)
+                    }
+                    _ => Err(self.undefined_operation(&[&expr])),
+                }
+            }
+        }
+    }
+
+    // `expr as T` converts between Int, Float and Decimal, which never mix implicitly
+    fn typecheck_convert(
+        &mut self,
+        expr: &mut Expr,
+        target: &TypeExpr,
+    ) -> Result<TinyType, TypeCheckError> {
+        let target: TinyType = self.resolve_type_expr(target)?;
+        let actual: TinyType = self.typecheck_expr(expr)?;
+        if is_numeric(&target) {
+            match self.resolve(&actual) {
+                source if is_numeric(&source) => return Ok(target),
+                TinyType::Var(id) if self.constrain(id, Bound::Numeric).is_ok() => {
+                    return Ok(target);
+                }
+                _ => {}
+            }
+        }
+        Err(TypeCheckError::InvalidConversion {
+            from: self.zonk(actual),
+            to: target,
+        })
+    }
+
+    fn typecheck_bool(&mut self, t: TinyType) -> Result<TinyType, TypeCheckError> {
+        self.unify(&t, &TinyType::Bool)
+            .map_err(|_| self.undefined_operation(&[&t]))?;
+        Ok(TinyType::Bool)
+    }
+
+    fn undefined_operation(&self, operands: &[&TinyType]) -> TypeCheckError {
+        TypeCheckError::UndefinedOperation(
+            operands.iter().map(|t| self.zonk((*t).clone())).collect(),
+        )
+    }
+
+    // Int and Float compare with each other; any other pair must share a type that is not a function
+    fn typecheck_equality(
+        &mut self,
+        left: TinyType,
+        right: TinyType,
+    ) -> Result<(), TypeCheckError> {
+        match (self.resolve(&left), self.resolve(&right)) {
+            (TinyType::Int | TinyType::Float, TinyType::Int | TinyType::Float) => Ok(()),
+            _ => {
+                self.unify(&left, &right)
+                    .map_err(|_| self.undefined_operation(&[&left, &right]))?;
+                match self.resolve(&left) {
+                    TinyType::Function { .. } => Err(self.undefined_operation(&[&left, &right])),
+                    _ => Ok(()),
+                }
+            }
+        }
+    }
+
+    // `+` concatenates two Strings and otherwise adds numbers
+    fn typecheck_add(
+        &mut self,
+        left: TinyType,
+        right: TinyType,
+    ) -> Result<TinyType, TypeCheckError> {
+        match (self.resolve(&left), self.resolve(&right)) {
+            (TinyType::String, _) | (_, TinyType::String) => {
+                self.unify(&left, &TinyType::String)
+                    .and_then(|_| self.unify(&right, &TinyType::String))
+                    .map_err(|_| self.undefined_operation(&[&left, &right]))?;
+                Ok(TinyType::String)
+            }
+            _ => self.typecheck_numeric(left, right, Bound::Addable),
+        }
+    }
+
+    fn typecheck_numeric(
+        &mut self,
+        left: TinyType,
+        right: TinyType,
+        bound: Bound,
+    ) -> Result<TinyType, TypeCheckError> {
+        if let Some(result) = self.numeric_result(&left, &right, bound)? {
+            return Ok(result);
+        }
+        // next to a Float the result is a Float either way, but the other operand is still
+        // checked once it is known
+        let result: TinyType = if [&left, &right]
+            .iter()
+            .any(|t| self.resolve(t) == TinyType::Float)
+        {
+            TinyType::Float
+        } else {
+            let result: TinyType = self.fresh();
+            if let TinyType::Var(id) = result {
+                self.bounds.insert(id, bound);
+            }
+            result
+        };
+        self.arith.push(Arith {
+            left,
+            right,
+            result: result.clone(),
+            bound,
+        });
+        Ok(result)
+    }
+
+    // the type of `left op right`, or None while an operand may still be Int or Float
+    fn numeric_result(
+        &mut self,
+        left: &TinyType,
+        right: &TinyType,
+        bound: Bound,
+    ) -> Result<Option<TinyType>, TypeCheckError> {
+        match (self.resolve(left), self.resolve(right)) {
+            (TinyType::Float, TinyType::Float)
+            | (TinyType::Int, TinyType::Float)
+            | (TinyType::Float, TinyType::Int) => Ok(Some(TinyType::Float)),
+            (TinyType::Int, TinyType::Int) => Ok(Some(TinyType::Int)),
+            // a Decimal only meets another Decimal, since mixing it with a Float would lose exactness
+            (TinyType::Decimal, TinyType::Decimal) => Ok(Some(TinyType::Decimal)),
+            (TinyType::String, TinyType::String) if bound == Bound::Addable => {
+                Ok(Some(TinyType::String))
+            }
+            // an operand of unknown type next to a Decimal or a String takes its type
+            (TinyType::Var(_), known @ (TinyType::Decimal | TinyType::String))
+            | (known @ (TinyType::Decimal | TinyType::String), TinyType::Var(_))
+                if bound.admits(&known) =>
+            {
+                self.unify(left, right)
+                    .map_err(|_| self.undefined_operation(&[left, right]))?;
+                Ok(Some(known))
+            }
+            (TinyType::Var(a), TinyType::Var(b)) => {
+                self.constrain(a, bound)
+                    .and_then(|_| self.constrain(b, bound))
+                    .map_err(|_| self.undefined_operation(&[left, right]))?;
+                Ok(None)
+            }
+            // next to an Int or a Float, the other operand may be either
+            (TinyType::Var(id), TinyType::Int | TinyType::Float)
+            | (TinyType::Int | TinyType::Float, TinyType::Var(id)) => {
+                self.constrain(id, Bound::Numeric)
+                    .map_err(|_| self.undefined_operation(&[left, right]))?;
+                Ok(None)
+            }
+            _ => Err(self.undefined_operation(&[left, right])),
+        }
+    }
+
+    // decides the pending operations whose operands became known, until none is left to decide
+    fn solve_arith(&mut self) -> Result<(), TypeCheckError> {
+        loop {
+            let pending: Vec<Arith> = std::mem::take(&mut self.arith);
+            let count: usize = pending.len();
+            for arith in pending {
+                match self.numeric_result(&arith.left, &arith.right, arith.bound)? {
+                    Some(result) => self
+                        .unify(&arith.result, &result)
+                        .map_err(|_| self.undefined_operation(&[&arith.left, &arith.right]))?,
+                    None => self.arith.push(arith),
                 }
             }
+            if self.arith.len() == count {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl TypeChecker {
+    // quantifies the variables of `t` that no other binding refers to, with their bounds and
+    // the operations over them, so that each use decides those operations for its own types
+    fn generalize(&mut self, t: TinyType) -> Result<Scheme, TypeCheckError> {
+        self.solve_arith()?;
+        let ty: TinyType = self.zonk(t);
+        let mut in_env: Vec<usize> = Vec::new();
+        for (_, scheme) in &self.env {
+            let mut vars: Vec<usize> = Vec::new();
+            self.free_vars(&self.zonk(scheme.ty.clone()), &mut vars);
+            in_env.extend(vars.into_iter().filter(|v| !scheme.vars.contains(v)));
+        }
+        // type parameters stay rigid until the generic function that declares them is checked
+        let quantifiable = |v: &usize| {
+            !in_env.contains(v)
+                && !self
+                    .type_params
+                    .iter()
+                    .any(|(_, t)| matches!(t, TinyType::Param { id, .. } if id == v))
+        };
+        let mut vars: Vec<usize> = Vec::new();
+        self.free_vars(&ty, &mut vars);
+        vars.retain(quantifiable);
+        // an operation over a quantified variable moves into the scheme, and so do the other
+        // variables it mentions, like the result of a comparison
+        let mut arith: Vec<Arith> = Vec::new();
+        let mut pending: Vec<Arith> = std::mem::take(&mut self.arith);
+        loop {
+            let (moved, kept): (Vec<Arith>, Vec<Arith>) = pending
+                .into_iter()
+                .partition(|a| self.arith_vars(a).iter().any(|v| vars.contains(v)));
+            pending = kept;
+            if moved.is_empty() {
+                break;
+            }
+            for a in moved {
+                let a = Arith {
+                    left: self.zonk(a.left),
+                    right: self.zonk(a.right),
+                    result: self.zonk(a.result),
+                    bound: a.bound,
+                };
+                for v in self.arith_vars(&a) {
+                    if quantifiable(&v) && !vars.contains(&v) {
+                        vars.push(v);
+                    }
+                }
+                arith.push(a);
+            }
+        }
+        // an operation that also involves an outer variable must still be decided for it
+        pending.extend(
+            arith
+                .iter()
+                .filter(|a| self.arith_vars(a).iter().any(|v| !vars.contains(v)))
+                .cloned(),
+        );
+        self.arith = pending;
+        let bounds: Vec<(usize, Bound)> = vars
+            .iter()
+            .filter_map(|v| self.bounds.get(v).map(|b| (*v, *b)))
+            .collect();
+        Ok(Scheme {
+            vars,
+            bounds,
+            arith,
+            ty,
+        })
+    }
+
+    fn arith_vars(&self, arith: &Arith) -> Vec<usize> {
+        let mut vars: Vec<usize> = Vec::new();
+        for t in [&arith.left, &arith.right, &arith.result] {
+            self.free_vars(&self.zonk(t.clone()), &mut vars);
+        }
+        vars
+    }
+
+    // generalizes the last `n` bindings, which must not see each other's variables
+    fn generalize_last(&mut self, n: usize) -> Result<(), TypeCheckError> {
+        let bindings: Vec<(String, Scheme)> = self.env.split_off(self.env.len() - n);
+        for (name, scheme) in bindings {
+            let scheme: Scheme = self.generalize(scheme.ty)?;
+            self.env.push((name, scheme));
+        }
+        Ok(())
+    }
+
+    fn instantiate(&mut self, scheme: &Scheme) -> TinyType {
+        if scheme.vars.is_empty() {
+            return scheme.ty.clone();
+        }
+        let fresh: HashMap<usize, TinyType> =
+            scheme.vars.iter().map(|v| (*v, self.fresh())).collect();
+        for (var, bound) in &scheme.bounds {
+            if let TinyType::Var(id) = fresh[var] {
+                self.bounds.insert(id, *bound);
+            }
+        }
+        for arith in &scheme.arith {
+            self.arith.push(Arith {
+                left: Self::substitute(arith.left.clone(), &fresh),
+                right: Self::substitute(arith.right.clone(), &fresh),
+                result: Self::substitute(arith.result.clone(), &fresh),
+                bound: arith.bound,
+            });
         }
+        Self::substitute(scheme.ty.clone(), &fresh)
+    }
+
+    fn substitute(t: TinyType, mapping: &HashMap<usize, TinyType>) -> TinyType {
+        match t {
+            TinyType::Var(id) => mapping.get(&id).cloned().unwrap_or(TinyType::Var(id)),
+            TinyType::Param { name, id } => mapping
+                .get(&id)
+                .cloned()
+                .unwrap_or(TinyType::Param { name, id }),
+            TinyType::Adt { name, args } => TinyType::Adt {
+                name,
+                args: args
+                    .into_iter()
+                    .map(|t| Self::substitute(t, mapping))
+                    .collect(),
+            },
+            TinyType::List(element) => {
+                TinyType::List(Box::new(Self::substitute(*element, mapping)))
+            }
+            TinyType::Tuple(elements) => TinyType::Tuple(
+                elements
+                    .into_iter()
+                    .map(|t| Self::substitute(t, mapping))
+                    .collect(),
+            ),
+            TinyType::Record(fields) => TinyType::Record(
+                fields
+                    .into_iter()
+                    .map(|(name, t)| (name, Self::substitute(t, mapping)))
+                    .collect(),
+            ),
+            TinyType::Function { params, ret } => TinyType::Function {
+                params: params
+                    .into_iter()
+                    .map(|t| Self::substitute(t, mapping))
+                    .collect(),
+                ret: Box::new(Self::substitute(*ret, mapping)),
+            },
+            other => other,
+        }
+    }
+
+    // unbound type variables and type parameters of a zonked type, each listed once
+    fn free_vars(&self, t: &TinyType, vars: &mut Vec<usize>) {
+        match t {
+            TinyType::Var(id) | TinyType::Param { id, .. } if !vars.contains(id) => vars.push(*id),
+            TinyType::Adt { args, .. } => args.iter().for_each(|t| self.free_vars(t, vars)),
+            TinyType::List(element) => self.free_vars(element, vars),
+            TinyType::Tuple(elements) => elements.iter().for_each(|t| self.free_vars(t, vars)),
+            TinyType::Record(fields) => fields.iter().for_each(|(_, t)| self.free_vars(t, vars)),
+            TinyType::Function { params, ret } => {
+                params.iter().for_each(|t| self.free_vars(t, vars));
+                self.free_vars(ret, vars);
+            }
+            _ => {}
+        }
+    }
+
+    fn fresh(&mut self) -> TinyType {
+        self.subst.push(None);
+        TinyType::Var(self.subst.len() - 1)
+    }
+
+    // follows bound type variables until reaching a concrete type or an unbound variable
+    fn resolve(&self, t: &TinyType) -> TinyType {
+        match t {
+            TinyType::Var(id) => match &self.subst[*id] {
+                Some(bound) => self.resolve(bound),
+                None => t.clone(),
+            },
+            _ => t.clone(),
+        }
+    }
+
+    // substitutes every bound type variable inside `t`
+    fn zonk(&self, t: TinyType) -> TinyType {
+        match self.resolve(&t) {
+            TinyType::Function { params, ret } => TinyType::Function {
+                params: params.into_iter().map(|p| self.zonk(p)).collect(),
+                ret: Box::new(self.zonk(*ret)),
+            },
+            TinyType::List(element) => TinyType::List(Box::new(self.zonk(*element))),
+            TinyType::Adt { name, args } => TinyType::Adt {
+                name,
+                args: args.into_iter().map(|t| self.zonk(t)).collect(),
+            },
+            TinyType::Tuple(elements) => {
+                TinyType::Tuple(elements.into_iter().map(|t| self.zonk(t)).collect())
+            }
+            TinyType::Record(fields) => TinyType::Record(
+                fields
+                    .into_iter()
+                    .map(|(name, t)| (name, self.zonk(t)))
+                    .collect(),
+            ),
+            resolved => resolved,
+        }
+    }
+
+    fn unify(&mut self, a: &TinyType, b: &TinyType) -> Result<(), ()> {
+        match (self.resolve(a), self.resolve(b)) {
+            (TinyType::Var(x), TinyType::Var(y)) if x == y => Ok(()),
+            (TinyType::Var(id), other) | (other, TinyType::Var(id)) => self.bind(id, other),
+            (
+                TinyType::Function {
+                    params: params_a,
+                    ret: ret_a,
+                },
+                TinyType::Function {
+                    params: params_b,
+                    ret: ret_b,
+                },
+            ) => {
+                if params_a.len() != params_b.len() {
+                    return Err(());
+                }
+                for (pa, pb) in params_a.iter().zip(params_b.iter()) {
+                    self.unify(pa, pb)?;
+                }
+                self.unify(&ret_a, &ret_b)
+            }
+            (TinyType::List(a), TinyType::List(b)) => self.unify(&a, &b),
+            (
+                TinyType::Adt {
+                    name: name_a,
+                    args: args_a,
+                },
+                TinyType::Adt {
+                    name: name_b,
+                    args: args_b,
+                },
+            ) if name_a == name_b => {
+                for (a, b) in args_a.iter().zip(args_b.iter()) {
+                    self.unify(a, b)?;
+                }
+                Ok(())
+            }
+            (TinyType::Tuple(a), TinyType::Tuple(b)) => {
+                if a.len() != b.len() {
+                    return Err(());
+                }
+                for (a, b) in a.iter().zip(b.iter()) {
+                    self.unify(a, b)?;
+                }
+                Ok(())
+            }
+            (TinyType::Record(a), TinyType::Record(b)) => {
+                if a.len() != b.len() || a.iter().zip(b.iter()).any(|((x, _), (y, _))| x != y) {
+                    return Err(());
+                }
+                for ((_, a), (_, b)) in a.iter().zip(b.iter()) {
+                    self.unify(a, b)?;
+                }
+                Ok(())
+            }
+            (a, b) if a == b => Ok(()),
+            _ => Err(()),
+        }
+    }
+
+    fn bind(&mut self, id: usize, t: TinyType) -> Result<(), ()> {
+        if self.occurs(id, &t) {
+            return Err(());
+        }
+        if let Some(bound) = self.bounds.get(&id).copied() {
+            match &t {
+                TinyType::Var(other) => self.constrain(*other, bound)?,
+                t if bound.admits(t) => {}
+                _ => return Err(()),
+            }
+        }
+        self.subst[id] = Some(t);
+        Ok(())
+    }
+
+    // fails when no type satisfies both bounds; a List-or-String that must also be Addable can
+    // only be a String
+    fn constrain(&mut self, id: usize, bound: Bound) -> Result<(), ()> {
+        let Some(existing) = self.bounds.get(&id).copied() else {
+            self.bounds.insert(id, bound);
+            return Ok(());
+        };
+        match existing.meet(bound) {
+            Some(bound) => {
+                self.bounds.insert(id, bound);
+                Ok(())
+            }
+            None if [existing, bound].contains(&Bound::Addable) => {
+                self.bounds.remove(&id);
+                self.subst[id] = Some(TinyType::String);
+                Ok(())
+            }
+            None => Err(()),
+        }
+    }
+
+    fn occurs(&self, id: usize, t: &TinyType) -> bool {
+        match self.resolve(t) {
+            TinyType::Var(other) => other == id,
+            TinyType::Function { params, ret } => {
+                params.iter().any(|p| self.occurs(id, p)) || self.occurs(id, &ret)
+            }
+            TinyType::List(element) => self.occurs(id, &element),
+            TinyType::Adt { args, .. } => args.iter().any(|t| self.occurs(id, t)),
+            TinyType::Tuple(elements) => elements.iter().any(|t| self.occurs(id, t)),
+            TinyType::Record(fields) => fields.iter().any(|(_, t)| self.occurs(id, t)),
+            _ => false,
+        }
+    }
+}
+
+// exhaustiveness and reachability of match arms, following Maranget's usefulness algorithm
+impl TypeChecker {
+    fn space(&self, pattern: &Pattern) -> Space {
+        match pattern {
+            Pattern::Wildcard | Pattern::Var(_) => Space::Any,
+            Pattern::Int(n) => Space::Ctor(Ctor::Int(n.clone()), Vec::new()),
+            Pattern::Bool(b) => Space::Ctor(Ctor::Bool(*b), Vec::new()),
+            Pattern::String(s) => Space::Ctor(Ctor::String(s.clone()), Vec::new()),
+            Pattern::Constructor { name, args } => Space::Ctor(
+                Ctor::Variant(self.constructors[name].1),
+                args.iter().map(|arg| self.space(arg)).collect(),
+            ),
+        }
+    }
+
+    // whether some value matched by `row` is matched by none of `rows`
+    fn useful(&self, rows: &[Vec<Space>], row: &[Space], types: &[TinyType]) -> bool {
+        let Some((head, tail)) = row.split_first() else {
+            return rows.is_empty();
+        };
+        match head {
+            Space::Ctor(ctor, args) => {
+                let fields: Vec<TinyType> = self.ctor_fields(&types[0], ctor);
+                self.useful_ctor(rows, ctor, args.clone(), tail, fields, &types[1..])
+            }
+            Space::Any => match self.signature(&types[0]) {
+                // every constructor of the type appears in the first column
+                Some(signature)
+                    if signature.iter().all(|(ctor, _)| {
+                        rows.iter()
+                            .any(|r| matches!(&r[0], Space::Ctor(c, _) if c == ctor))
+                    }) =>
+                {
+                    signature.into_iter().any(|(ctor, fields)| {
+                        let args: Vec<Space> = vec![Space::Any; fields.len()];
+                        self.useful_ctor(rows, &ctor, args, tail, fields, &types[1..])
+                    })
+                }
+                _ => {
+                    let rows: Vec<Vec<Space>> = rows
+                        .iter()
+                        .filter(|r| matches!(r[0], Space::Any))
+                        .map(|r| r[1..].to_vec())
+                        .collect();
+                    self.useful(&rows, tail, &types[1..])
+                }
+            },
+        }
+    }
+
+    // `useful` on the rows that match `ctor`, with its fields expanded into columns
+    fn useful_ctor(
+        &self,
+        rows: &[Vec<Space>],
+        ctor: &Ctor,
+        args: Vec<Space>,
+        tail: &[Space],
+        fields: Vec<TinyType>,
+        types: &[TinyType],
+    ) -> bool {
+        let rows: Vec<Vec<Space>> = rows
+            .iter()
+            .filter_map(|r| {
+                let mut specialized: Vec<Space> = match &r[0] {
+                    Space::Ctor(c, a) if c == ctor => a.clone(),
+                    Space::Ctor(_, _) => return None,
+                    Space::Any => vec![Space::Any; fields.len()],
+                };
+                specialized.extend_from_slice(&r[1..]);
+                Some(specialized)
+            })
+            .collect();
+        let row: Vec<Space> = args.into_iter().chain(tail.iter().cloned()).collect();
+        let types: Vec<TinyType> = fields.into_iter().chain(types.iter().cloned()).collect();
+        self.useful(&rows, &row, &types)
+    }
+
+    // every constructor of a type with finitely many, or None for types like Int
+    fn signature(&self, t: &TinyType) -> Option<Vec<(Ctor, Vec<TinyType>)>> {
+        match self.resolve(t) {
+            TinyType::Bool => Some(vec![
+                (Ctor::Bool(true), Vec::new()),
+                (Ctor::Bool(false), Vec::new()),
+            ]),
+            TinyType::Adt { name, args } => Some(
+                (0..self.adts[&name].variants.len())
+                    .map(|tag| (Ctor::Variant(tag), self.adt_fields(&name, &args, tag)))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    fn ctor_fields(&self, t: &TinyType, ctor: &Ctor) -> Vec<TinyType> {
+        match (self.resolve(t), ctor) {
+            (TinyType::Adt { name, args }, Ctor::Variant(tag)) => {
+                self.adt_fields(&name, &args, *tag)
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::tokenizer::Tokenizer;
+
+    fn typecheck(program: &str) -> Result<Option<TinyType>, TypeCheckError> {
+        let tokens = Tokenizer::tokenize(program).unwrap_or_else(|_| panic!("{}", program));
+        let mut ast: Vec<Stmt> = Parser::new(tokens)
+            .parse()
+            .unwrap_or_else(|_| panic!("{}", program));
+        TypeChecker::typecheck(&mut ast)
+    }
+
+    #[test]
+    fn rejects_capturing_a_local_var() {
+        let result = typecheck("fn g() = { var x = 1; let f = fn() => x; x = 2; f() }; g()");
+        assert!(matches!(result, Err(TypeCheckError::CaptureOfLocalVar(name)) if name == "x"));
+    }
+
+    #[test]
+    fn rejects_a_match_missing_a_variant() {
+        let result = typecheck("type S = A | B(Int) | C; match A { A => 1, C => 3 }");
+        assert!(matches!(
+            result,
+            Err(TypeCheckError::NonExhaustiveMatch(TinyType::Adt { name, .. })) if name == "S"
+        ));
+    }
+
+    #[test]
+    fn rejects_an_arm_after_a_wildcard() {
+        let result = typecheck("type S = A | B(Int) | C; match A { _ => 0, A => 1 }");
+        assert!(matches!(
+            result,
+            Err(TypeCheckError::UnreachableArm(Pattern::Constructor { name, .. })) if name == "A"
+        ));
+    }
+
+    #[test]
+    fn rejects_destructuring_a_tuple_of_another_arity() {
+        let result = typecheck("let (a, b) = (1, 2, 3); a");
+        assert!(matches!(
+            result,
+            Err(TypeCheckError::TupleArityMismatch {
+                expected: 2,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_tuple_with_a_mismatched_element() {
+        let result = typecheck("fn f(p: (Int, Bool)) = p; f((1, 2))");
+        assert!(matches!(
+            result,
+            Err(TypeCheckError::ArgumentTypeMismatch { expected, actual })
+                if expected == TinyType::Tuple(vec![TinyType::Int, TinyType::Bool])
+                    && actual == TinyType::Tuple(vec![TinyType::Int, TinyType::Int])
+        ));
+    }
+
+    #[test]
+    fn rejects_a_binding_annotation_mismatch() {
+        let result = typecheck("let x: Int = \"a\"; x");
+        assert!(matches!(
+            result,
+            Err(TypeCheckError::AnnotationMismatch {
+                declared: TinyType::Int,
+                actual: TinyType::String
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_return_annotation_mismatch() {
+        let result = typecheck("fn f() -> Int = \"a\"; f()");
+        assert!(matches!(
+            result,
+            Err(TypeCheckError::AnnotationMismatch {
+                declared: TinyType::Int,
+                actual: TinyType::String
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_arithmetic_on_an_option() {
+        let result = typecheck("Some(1) + 1");
+        assert!(matches!(
+            result,
+            Err(TypeCheckError::UndefinedOperation(operands))
+                if matches!(&operands[..], [TinyType::Adt { name, .. }, TinyType::Int] if name == "Option")
+        ));
+    }
+
+    #[test]
+    fn rejects_a_default_of_another_type() {
+        let result = typecheck("Some(2) ?? \"a\"");
+        assert!(matches!(
+            result,
+            Err(TypeCheckError::DefaultTypeMismatch {
+                expected: TinyType::Int,
+                actual: TinyType::String
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_break_and_continue_outside_a_loop() {
+        let cases: [(&str, &str); 3] = [
+            ("break", "break"),
+            ("continue", "continue"),
+            ("while true { let f = fn() => break; f() }", "break"),
+        ];
+        for (program, keyword) in cases {
+            let result = typecheck(program);
+            assert!(
+                matches!(&result, Err(TypeCheckError::OutsideLoop(k)) if k == keyword),
+                "{}",
+                program
+            );
+        }
+    }
+
+    #[test]
+    fn arithmetic_on_a_parameter_mixes_int_and_float_at_each_call() {
+        let half = typecheck("fn half(x) = x / 2; half(3.0)");
+        assert!(matches!(half, Ok(Some(TinyType::Float))));
+        let shift = typecheck("fn f(x) = x + 0.5; f(1)");
+        assert!(matches!(shift, Ok(Some(TinyType::Float))));
+        let square = typecheck("fn sq(x) = x * x; sq(2)");
+        assert!(matches!(square, Ok(Some(TinyType::Int))));
+    }
+
+    #[test]
+    fn operator_constrained_function_is_polymorphic() {
+        let result = typecheck("fn add(a, b) = a + b; let n = add(1, 2); add(\"a\", \"b\")");
+        assert!(matches!(result, Ok(Some(TinyType::String))));
+        let result = typecheck("fn lt(a, b) = a < b; lt(1, 2) && lt(1.5, 2)");
+        assert!(matches!(result, Ok(Some(TinyType::Bool))));
+    }
+
+    #[test]
+    fn rejects_operands_of_conflicting_types() {
+        let result = typecheck("fn add(a, b) = a + b; add(1, \"a\")");
+        assert!(matches!(
+            result,
+            Err(TypeCheckError::UndefinedOperation(operands))
+                if operands == vec![TinyType::Int, TinyType::String]
+        ));
+        let result = typecheck("fn f(x) = x - 1; f(\"a\")");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn len_accepts_a_list_or_a_string_parameter() {
+        let result = typecheck("fn f(xs) = len(xs); f(\"abc\") + f([1, 2])");
+        assert!(matches!(result, Ok(Some(TinyType::Int))));
+        assert!(typecheck("fn f(xs) = len(xs); f(1)").is_err());
     }
 }
@@ -1,5 +1,7 @@
 pub mod ast;
 pub mod opcode;
+pub mod tiny_decimal;
+pub mod tiny_int;
 pub mod tiny_object;
 pub mod tiny_type;
 pub mod token;
@@ -1,6 +1,114 @@
+use super::tiny_decimal::TinyDecimal;
+use super::tiny_int::TinyInt;
+
 #[derive(Debug, Clone)]
 pub enum Stmt {
     Expr(Expr),
+    Let {
+        name: String,
+        value: Expr,
+    },
+    LetTuple {
+        names: Vec<String>,
+        value: Expr,
+    },
+    // `var`: a binding that can be assigned to
+    LetMut {
+        name: String,
+        value: Expr,
+    },
+    Fn {
+        name: String,
+        // `<T, U>` after the name
+        type_params: Vec<String>,
+        params: Vec<String>,
+        // annotation of each parameter; a return type annotates the body
+        param_types: Vec<Option<TypeExpr>>,
+        body: Expr,
+    },
+    Type {
+        name: String,
+        params: Vec<String>,
+        // constructors with the types of their fields; the position of a constructor is its tag
+        variants: Vec<(String, Vec<TypeExpr>)>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum TypeExpr {
+    Named {
+        name: String,
+        args: Vec<TypeExpr>,
+    },
+    Record(Vec<(String, TypeExpr)>),
+    Tuple(Vec<TypeExpr>),
+    Function {
+        params: Vec<TypeExpr>,
+        ret: Box<TypeExpr>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Wildcard,
+    Var(String),
+    Int(TinyInt),
+    Bool(bool),
+    String(String),
+    Constructor { name: String, args: Vec<Pattern> },
+}
+
+// declarations that every program starts with
+pub fn prelude() -> Vec<Stmt> {
+    vec![Stmt::Type {
+        name: String::from("Option"),
+        params: vec![String::from("T")],
+        variants: vec![
+            (String::from("None"), Vec::new()),
+            (
+                String::from("Some"),
+                vec![TypeExpr::Named {
+                    name: String::from("T"),
+                    args: Vec::new(),
+                }],
+            ),
+        ],
+    }]
+}
+
+// names of the functions defined by consecutive `fn` statements from `stmts[i]`;
+// they are declared together so that they can call each other.
+// Empty when `stmts[i]` continues a group that starts earlier.
+pub fn fn_group(stmts: &[Stmt], i: usize) -> Vec<String> {
+    if i > 0 && matches!(stmts[i - 1], Stmt::Fn { .. }) {
+        return Vec::new();
+    }
+    stmts[i..]
+        .iter()
+        .map_while(|stmt| match stmt {
+            Stmt::Fn { name, .. } => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn pattern_to_string(p: Pattern) -> String {
+    match p {
+        Pattern::Wildcard => String::from("_"),
+        Pattern::Var(name) => name,
+        Pattern::Int(n) => n.to_string(),
+        Pattern::Bool(b) => b.to_string(),
+        Pattern::String(s) => format!("{:?}", s),
+        Pattern::Constructor { name, args } if args.is_empty() => name,
+        Pattern::Constructor { name, args } => format!(
+            "{}({})",
+            name,
+            args.into_iter()
+                .map(pattern_to_string)
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -10,13 +118,122 @@ pub enum Expr {
         thn: Box<Expr>,
         els: Box<Expr>,
     },
-    Int(i32),
+    Let {
+        name: String,
+        value: Box<Expr>,
+        body: Box<Expr>,
+    },
+    LetTuple {
+        names: Vec<String>,
+        value: Box<Expr>,
+        body: Box<Expr>,
+    },
+    LetMut {
+        name: String,
+        value: Box<Expr>,
+        body: Box<Expr>,
+    },
+    Assign {
+        name: String,
+        value: Box<Expr>,
+    },
+    // expressions evaluated in order; the value of the block is the value of the last one
+    Block(Vec<Expr>),
+    While {
+        cond: Box<Expr>,
+        body: Box<Expr>,
+    },
+    Break,
+    Continue,
+    // raises an error value, which unwinds to the nearest enclosing `try`
+    Throw(Box<Expr>),
+    // `try { body } catch name { handler }`
+    Try {
+        body: Box<Expr>,
+        name: String,
+        handler: Box<Expr>,
+    },
+    Var(String),
+    List(Vec<Expr>),
+    Tuple(Vec<Expr>),
+    Index {
+        expr: Box<Expr>,
+        index: Box<Expr>,
+    },
+    Slice {
+        expr: Box<Expr>,
+        start: Box<Expr>,
+        end: Box<Expr>,
+    },
+    Len(Box<Expr>),
+    Record(Vec<(String, Expr)>),
+    // offsets are resolved by the typechecker from the type of the record
+    Field {
+        expr: Box<Expr>,
+        field: String,
+        offset: Option<usize>,
+    },
+    RecordUpdate {
+        record: Box<Expr>,
+        fields: Vec<(String, Expr)>,
+        offsets: Option<Vec<usize>>,
+    },
+    Match {
+        scrutinee: Box<Expr>,
+        arms: Vec<(Pattern, Expr)>,
+    },
+    Lambda {
+        params: Vec<String>,
+        param_types: Vec<Option<TypeExpr>>,
+        body: Box<Expr>,
+    },
+    // `expr` must have the written type; an Int is widened when a Float is written
+    Annotated {
+        expr: Box<Expr>,
+        ty: TypeExpr,
+    },
+    // `expr as Decimal`: an explicit conversion between Int, Float and Decimal
+    Convert {
+        expr: Box<Expr>,
+        target: TypeExpr,
+    },
+    Call {
+        callee: Box<Expr>,
+        args: Vec<Expr>,
+    },
+    // `option ?? fallback`: the value inside a `Some`, or `fallback` for `None`
+    Default {
+        option: Box<Expr>,
+        fallback: Box<Expr>,
+    },
+    Int(TinyInt),
     Bool(bool),
-    Float(f32),
+    Float(f64),
+    Decimal(TinyDecimal),
+    String(String),
     BinOp(Box<BinaryOperation>),
+    UnaryOp(Box<UnaryOperation>),
 }
 
 #[derive(Debug, Clone)]
 pub enum BinaryOperation {
     Add { left: Box<Expr>, right: Box<Expr> },
+    Sub { left: Box<Expr>, right: Box<Expr> },
+    Mul { left: Box<Expr>, right: Box<Expr> },
+    Div { left: Box<Expr>, right: Box<Expr> },
+    Mod { left: Box<Expr>, right: Box<Expr> },
+    Eq { left: Box<Expr>, right: Box<Expr> },
+    Ne { left: Box<Expr>, right: Box<Expr> },
+    Lt { left: Box<Expr>, right: Box<Expr> },
+    Le { left: Box<Expr>, right: Box<Expr> },
+    Gt { left: Box<Expr>, right: Box<Expr> },
+    Ge { left: Box<Expr>, right: Box<Expr> },
+    And { left: Box<Expr>, right: Box<Expr> },
+    Or { left: Box<Expr>, right: Box<Expr> },
+}
+
+#[derive(Debug, Clone)]
+pub enum UnaryOperation {
+    Not { expr: Box<Expr> },
+    Neg { expr: Box<Expr> },
 }
@@ -1,11 +1,73 @@
-use super::tiny_object::TinyObject;
+use super::tiny_object::{Function, TinyObject};
+use std::rc::Rc;
 
 #[derive(Debug, Clone)]
 pub enum OpCode {
     Push(TinyObject),
     Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    MakeList(usize),
+    MakeTuple(usize),
+    TupleField(usize),
+    Index,
+    Slice,
+    Len,
+    // offset of each value on the stack, in the order they were pushed
+    MakeRecord(Vec<usize>),
+    GetField(usize),
+    SetField(usize),
+    MakeVariant {
+        name: Rc<str>,
+        tag: usize,
+        arity: usize,
+    },
+    IsVariant(usize),
+    VariantField(usize),
+    Not,
+    Negate,
+    // the conversions of `as`; each also accepts a value that already has its type
+    ToInt,
+    ToFloat,
+    ToDecimal,
     JumpIfFalse(usize),
     Jump(usize),
-    #[allow(dead_code)]
+    // a loop remembers the stack height so that `break` and `continue` can drop pending values
+    EnterLoop,
+    ExitLoop,
+    Break(usize),
+    Continue(usize),
+    // installs a handler at the given address until the matching `EndTry`
+    Try(usize),
+    EndTry,
+    Throw,
+    LoadLocal(usize),
+    StoreLocal(usize),
+    LoadGlobal(usize),
+    StoreGlobal(usize),
+    LoadUpvalue(usize),
+    MakeClosure {
+        function: Rc<Function>,
+        captures: Vec<Capture>,
+    },
+    Call(usize),
+    // a call in tail position; replaces the frame of the caller instead of pushing one
+    TailCall(usize),
+    Return,
     Pop,
 }
+
+// where a closure finds a captured variable when it is created
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Capture {
+    Local(usize),
+    Upvalue(usize),
+}
@@ -0,0 +1,298 @@
+use super::tiny_int::TinyInt;
+use std::cmp::Ordering;
+use std::fmt;
+
+// an exact decimal number: `units / 10^scale`; `0.10d` has 10 units at scale 2
+#[derive(Clone)]
+pub struct TinyDecimal {
+    units: TinyInt,
+    scale: u32,
+}
+
+// how a result with more fractional digits than the configured scale is rounded
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Rounding {
+    // to the nearest, ties to the even neighbor
+    #[default]
+    HalfEven,
+    // to the nearest, ties away from zero
+    HalfUp,
+    // toward zero
+    Down,
+    // away from zero
+    Up,
+    Floor,
+    Ceiling,
+}
+
+impl TinyDecimal {
+    // the digits before and after the point, without sign or separators
+    pub fn parse(int_digits: &str, fraction_digits: &str) -> Option<Self> {
+        let digits: String = format!("{}{}", int_digits, fraction_digits);
+        Some(Self {
+            units: TinyInt::parse(&digits, 10)?,
+            scale: fraction_digits.len() as u32,
+        })
+    }
+
+    pub fn from_int(n: &TinyInt) -> Self {
+        Self {
+            units: n.clone(),
+            scale: 0,
+        }
+    }
+
+    // the decimal digits Rust prints for `f`, rounded to `scale`; None for NaN and infinities
+    pub fn from_f64(f: f64, scale: u32, rounding: Rounding) -> Option<Self> {
+        if !f.is_finite() {
+            return None;
+        }
+        let text: String = format!("{}", f.abs());
+        let (int_digits, fraction_digits) = text.split_once('.').unwrap_or((&text, ""));
+        let abs: Self = Self::parse(int_digits, fraction_digits)?;
+        let value: Self = if f < 0.0 { abs.neg() } else { abs };
+        Some(value.round(scale, rounding))
+    }
+
+    // truncates toward zero
+    pub fn to_int(&self) -> TinyInt {
+        self.units
+            .checked_div(&pow10(self.scale))
+            .expect("a power of ten is never zero")
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.to_string()
+            .parse::<f64>()
+            .expect("a decimal prints as a valid float")
+    }
+
+    pub fn neg(&self) -> Self {
+        Self {
+            units: -&self.units,
+            scale: self.scale,
+        }
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let (a, b, scale) = Self::align(self, other);
+        Self {
+            units: &a + &b,
+            scale,
+        }
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        let (a, b, scale) = Self::align(self, other);
+        Self {
+            units: &a - &b,
+            scale,
+        }
+    }
+
+    pub fn mul(&self, other: &Self, scale: u32, rounding: Rounding) -> Self {
+        Self {
+            units: &self.units * &other.units,
+            scale: self.scale + other.scale,
+        }
+        .round(scale, rounding)
+    }
+
+    // rounded to `scale` digits, then trailing zeros are dropped down to the scale of the
+    // operands; None when `other` is zero
+    pub fn div(&self, other: &Self, scale: u32, rounding: Rounding) -> Option<Self> {
+        if other.units.is_zero() {
+            return None;
+        }
+        // self / other = (self.units * 10^(other.scale + scale)) / (other.units * 10^self.scale)
+        let numerator: TinyInt = &self.units * &pow10(other.scale + scale);
+        let denominator: TinyInt = &other.units * &pow10(self.scale);
+        let mut quotient = Self {
+            units: divide(&numerator, &denominator, rounding),
+            scale,
+        };
+        let ten: TinyInt = TinyInt::from(10);
+        while quotient.scale > self.scale.max(other.scale)
+            && quotient
+                .units
+                .checked_rem(&ten)
+                .is_some_and(|r| r.is_zero())
+        {
+            quotient.units = quotient.units.checked_div(&ten)?;
+            quotient.scale -= 1;
+        }
+        Some(quotient)
+    }
+
+    // the remainder of the division truncated toward zero; None when `other` is zero
+    pub fn rem(&self, other: &Self) -> Option<Self> {
+        let (a, b, scale) = Self::align(self, other);
+        Some(Self {
+            units: a.checked_rem(&b)?,
+            scale,
+        })
+    }
+
+    pub fn round(&self, scale: u32, rounding: Rounding) -> Self {
+        if self.scale <= scale {
+            return self.clone();
+        }
+        Self {
+            units: divide(&self.units, &pow10(self.scale - scale), rounding),
+            scale,
+        }
+    }
+
+    // the units of both at the larger of their scales
+    fn align(a: &Self, b: &Self) -> (TinyInt, TinyInt, u32) {
+        let scale: u32 = a.scale.max(b.scale);
+        (
+            &a.units * &pow10(scale - a.scale),
+            &b.units * &pow10(scale - b.scale),
+            scale,
+        )
+    }
+}
+
+fn pow10(exponent: u32) -> TinyInt {
+    let ten: TinyInt = TinyInt::from(10);
+    (0..exponent).fold(TinyInt::from(1), |acc, _| &acc * &ten)
+}
+
+// `numerator / denominator` rounded to an integer; `denominator` must not be zero
+fn divide(numerator: &TinyInt, denominator: &TinyInt, rounding: Rounding) -> TinyInt {
+    let quotient: TinyInt = numerator
+        .checked_div(denominator)
+        .expect("the denominator is not zero");
+    let remainder: TinyInt = numerator
+        .checked_rem(denominator)
+        .expect("the denominator is not zero");
+    if remainder.is_zero() {
+        return quotient;
+    }
+    let negative: bool = remainder.is_positive() != denominator.is_positive();
+    let away: TinyInt = &quotient + &TinyInt::from(if negative { -1 } else { 1 });
+    // how the dropped part compares with one half
+    let half: Ordering = abs(&(&remainder + &remainder)).cmp(&abs(denominator));
+    let is_even: bool = quotient
+        .checked_rem(&TinyInt::from(2))
+        .is_some_and(|r| r.is_zero());
+    let round_away: bool = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => true,
+        Rounding::Floor => negative,
+        Rounding::Ceiling => !negative,
+        Rounding::HalfUp => half != Ordering::Less,
+        Rounding::HalfEven => half == Ordering::Greater || (half == Ordering::Equal && !is_even),
+    };
+    if round_away { away } else { quotient }
+}
+
+fn abs(n: &TinyInt) -> TinyInt {
+    if n.is_positive() || n.is_zero() {
+        n.clone()
+    } else {
+        -n
+    }
+}
+
+// equal values compare equal whatever their scale, so `0.10d == 0.1d`
+impl PartialEq for TinyDecimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TinyDecimal {}
+
+impl Ord for TinyDecimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b, _) = Self::align(self, other);
+        a.cmp(&b)
+    }
+}
+
+impl PartialOrd for TinyDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// keeps the scale, so `0.10d` prints as `0.10`
+impl fmt::Display for TinyDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits: String = abs(&self.units).to_string();
+        let scale: usize = self.scale as usize;
+        let digits: String = format!("{:0>width$}", digits, width = scale + 1);
+        let (int_digits, fraction_digits) = digits.split_at(digits.len() - scale);
+        if !self.units.is_positive() && !self.units.is_zero() {
+            write!(f, "-")?;
+        }
+        match scale {
+            0 => write!(f, "{}", int_digits),
+            _ => write!(f, "{}.{}", int_digits, fraction_digits),
+        }
+    }
+}
+
+impl fmt::Debug for TinyDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(text: &str) -> TinyDecimal {
+        let (sign, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text),
+        };
+        let (int_digits, fraction_digits) = digits.split_once('.').unwrap_or((digits, ""));
+        let abs: TinyDecimal = TinyDecimal::parse(int_digits, fraction_digits).unwrap();
+        if sign { abs.neg() } else { abs }
+    }
+
+    #[test]
+    fn division_ties_follow_rounding_mode() {
+        let cases: [(Rounding, &str, &str); 6] = [
+            (Rounding::HalfEven, "2", "-2"),
+            (Rounding::HalfUp, "3", "-3"),
+            (Rounding::Down, "2", "-2"),
+            (Rounding::Up, "3", "-3"),
+            (Rounding::Floor, "2", "-3"),
+            (Rounding::Ceiling, "3", "-2"),
+        ];
+        for (rounding, positive, negative) in cases {
+            let two: TinyDecimal = decimal("2");
+            let quotient = |n: &str| decimal(n).div(&two, 0, rounding).unwrap().to_string();
+            assert_eq!(quotient("5"), positive, "5 / 2 with {:?}", rounding);
+            assert_eq!(quotient("-5"), negative, "-5 / 2 with {:?}", rounding);
+        }
+    }
+
+    #[test]
+    fn addition_is_exact() {
+        assert_eq!(decimal("0.1").add(&decimal("0.2")), decimal("0.3"));
+        assert_eq!(decimal("0.1").add(&decimal("0.2")).to_string(), "0.3");
+    }
+
+    #[test]
+    fn equal_values_ignore_scale() {
+        assert_eq!(decimal("0.10"), decimal("0.1"));
+        assert_eq!(decimal("0.10").to_string(), "0.10");
+        assert!(decimal("-0.5") < decimal("0.25"));
+    }
+
+    #[test]
+    fn division_by_zero_is_none() {
+        assert!(
+            decimal("1")
+                .div(&decimal("0.0"), 20, Rounding::HalfEven)
+                .is_none()
+        );
+        assert!(decimal("1").rem(&decimal("0")).is_none());
+    }
+}
@@ -0,0 +1,555 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::rc::Rc;
+
+// the value of an Int; arithmetic stays on i64 and promotes to a big integer when a result
+// does not fit, so every result is exact
+#[derive(Clone, PartialEq, Eq)]
+pub enum TinyInt {
+    Small(i64),
+    // never holds a value that fits in i64, so each value has exactly one representation
+    Big(Rc<BigInt>),
+}
+
+// sign and magnitude; `magnitude` holds base 2^32 digits, least significant first,
+// without leading zero digits, so zero is an empty magnitude
+#[derive(Clone, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+impl TinyInt {
+    // `digits` in base `radix`, without sign or separators
+    pub fn parse(digits: &str, radix: u32) -> Option<Self> {
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return None;
+        }
+        match i64::from_str_radix(digits, radix) {
+            Ok(n) => Some(TinyInt::Small(n)),
+            Err(_) => Some(Self::from_big(BigInt::parse(digits, radix))),
+        }
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        match self {
+            TinyInt::Small(n) => Some(*n),
+            TinyInt::Big(_) => None,
+        }
+    }
+
+    // the low 64 bits in two's complement, as a wrapping i64 operation would give
+    pub fn wrapping_to_i64(&self) -> i64 {
+        match self {
+            TinyInt::Small(n) => *n,
+            TinyInt::Big(n) => n.wrapping_to_i64(),
+        }
+    }
+
+    // the nearest i64
+    pub fn saturating_to_i64(&self) -> i64 {
+        match self {
+            TinyInt::Small(n) => *n,
+            TinyInt::Big(n) if n.negative => i64::MIN,
+            TinyInt::Big(_) => i64::MAX,
+        }
+    }
+
+    pub fn to_usize(&self) -> Option<usize> {
+        self.to_i64().and_then(|n| usize::try_from(n).ok())
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            TinyInt::Small(n) => *n as f64,
+            TinyInt::Big(n) => n.to_f64(),
+        }
+    }
+
+    pub fn is_positive(&self) -> bool {
+        match self {
+            TinyInt::Small(n) => *n > 0,
+            TinyInt::Big(n) => !n.negative,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        matches!(self, TinyInt::Small(0))
+    }
+
+    // truncates toward zero like i64 division; None when `other` is zero
+    pub fn checked_div(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        if let (TinyInt::Small(a), TinyInt::Small(b)) = (self, other)
+            && let Some(n) = a.checked_div(*b)
+        {
+            return Some(TinyInt::Small(n));
+        }
+        let (quotient, _) = self.to_big().div_rem(&other.to_big());
+        Some(Self::from_big(quotient))
+    }
+
+    // the remainder has the sign of `self`, like i64; None when `other` is zero
+    pub fn checked_rem(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        if let (TinyInt::Small(a), TinyInt::Small(b)) = (self, other)
+            && let Some(n) = a.checked_rem(*b)
+        {
+            return Some(TinyInt::Small(n));
+        }
+        let (_, remainder) = self.to_big().div_rem(&other.to_big());
+        Some(Self::from_big(remainder))
+    }
+
+    fn to_big(&self) -> BigInt {
+        match self {
+            TinyInt::Small(n) => BigInt::from_i64(*n),
+            TinyInt::Big(n) => n.as_ref().clone(),
+        }
+    }
+
+    fn from_big(n: BigInt) -> Self {
+        match n.to_i64() {
+            Some(n) => TinyInt::Small(n),
+            None => TinyInt::Big(Rc::new(n)),
+        }
+    }
+}
+
+impl From<i64> for TinyInt {
+    fn from(n: i64) -> Self {
+        TinyInt::Small(n)
+    }
+}
+
+impl Add for &TinyInt {
+    type Output = TinyInt;
+
+    fn add(self, other: &TinyInt) -> TinyInt {
+        if let (TinyInt::Small(a), TinyInt::Small(b)) = (self, other)
+            && let Some(n) = a.checked_add(*b)
+        {
+            return TinyInt::Small(n);
+        }
+        TinyInt::from_big(self.to_big().add(&other.to_big()))
+    }
+}
+
+impl Sub for &TinyInt {
+    type Output = TinyInt;
+
+    fn sub(self, other: &TinyInt) -> TinyInt {
+        if let (TinyInt::Small(a), TinyInt::Small(b)) = (self, other)
+            && let Some(n) = a.checked_sub(*b)
+        {
+            return TinyInt::Small(n);
+        }
+        TinyInt::from_big(self.to_big().add(&other.to_big().neg()))
+    }
+}
+
+impl Mul for &TinyInt {
+    type Output = TinyInt;
+
+    fn mul(self, other: &TinyInt) -> TinyInt {
+        if let (TinyInt::Small(a), TinyInt::Small(b)) = (self, other)
+            && let Some(n) = a.checked_mul(*b)
+        {
+            return TinyInt::Small(n);
+        }
+        TinyInt::from_big(self.to_big().mul(&other.to_big()))
+    }
+}
+
+impl Neg for &TinyInt {
+    type Output = TinyInt;
+
+    fn neg(self) -> TinyInt {
+        match self {
+            TinyInt::Small(n) => match n.checked_neg() {
+                Some(n) => TinyInt::Small(n),
+                // -i64::MIN has no i64 representation
+                None => TinyInt::from_big(BigInt::from_i64(*n).neg()),
+            },
+            TinyInt::Big(n) => TinyInt::from_big(n.neg()),
+        }
+    }
+}
+
+impl Ord for TinyInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (TinyInt::Small(a), TinyInt::Small(b)) => a.cmp(b),
+            _ => self.to_big().cmp(&other.to_big()),
+        }
+    }
+}
+
+impl PartialOrd for TinyInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for TinyInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TinyInt::Small(n) => write!(f, "{}", n),
+            TinyInt::Big(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+// printed as the plain number, so that an Int shows up as `Int(42)`
+impl fmt::Debug for TinyInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl BigInt {
+    fn from_i64(n: i64) -> Self {
+        let abs: u64 = n.unsigned_abs();
+        Self::new(n < 0, vec![abs as u32, (abs >> 32) as u32])
+    }
+
+    fn new(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        // zero is never negative
+        let negative: bool = negative && !magnitude.is_empty();
+        Self {
+            negative,
+            magnitude,
+        }
+    }
+
+    fn parse(digits: &str, radix: u32) -> Self {
+        let mut magnitude: Vec<u32> = Vec::new();
+        for c in digits.chars() {
+            let digit: u32 = c
+                .to_digit(radix)
+                .expect("digits are checked by TinyInt::parse");
+            mul_small_add(&mut magnitude, radix, digit);
+        }
+        Self::new(false, magnitude)
+    }
+
+    fn to_i64(&self) -> Option<i64> {
+        if self.magnitude.len() > 2 {
+            return None;
+        }
+        let abs: u64 = self
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0u64, |acc, digit| (acc << 32) | *digit as u64);
+        match self.negative {
+            true => 0i64.checked_sub_unsigned(abs),
+            false => i64::try_from(abs).ok(),
+        }
+    }
+
+    fn wrapping_to_i64(&self) -> i64 {
+        let low: u64 = self
+            .magnitude
+            .iter()
+            .take(2)
+            .rev()
+            .fold(0u64, |acc, digit| (acc << 32) | *digit as u64);
+        match self.negative {
+            true => low.wrapping_neg() as i64,
+            false => low as i64,
+        }
+    }
+
+    fn to_f64(&self) -> f64 {
+        let abs: f64 = self
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0.0, |acc, digit| acc * 4294967296.0 + *digit as f64);
+        if self.negative { -abs } else { abs }
+    }
+
+    fn neg(&self) -> Self {
+        Self::new(!self.negative, self.magnitude.clone())
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        if self.negative == other.negative {
+            return Self::new(
+                self.negative,
+                add_magnitudes(&self.magnitude, &other.magnitude),
+            );
+        }
+        match cmp_magnitudes(&self.magnitude, &other.magnitude) {
+            Ordering::Less => Self::new(
+                other.negative,
+                sub_magnitudes(&other.magnitude, &self.magnitude),
+            ),
+            _ => Self::new(
+                self.negative,
+                sub_magnitudes(&self.magnitude, &other.magnitude),
+            ),
+        }
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let mut product: Vec<u32> = vec![0; self.magnitude.len() + other.magnitude.len()];
+        for (i, a) in self.magnitude.iter().enumerate() {
+            let mut carry: u64 = 0;
+            for (j, b) in other.magnitude.iter().enumerate() {
+                let t: u64 = *a as u64 * *b as u64 + product[i + j] as u64 + carry;
+                product[i + j] = t as u32;
+                carry = t >> 32;
+            }
+            product[i + other.magnitude.len()] = carry as u32;
+        }
+        Self::new(self.negative != other.negative, product)
+    }
+
+    // quotient truncated toward zero and the remainder with the sign of `self`
+    fn div_rem(&self, other: &Self) -> (Self, Self) {
+        let (quotient, remainder) = div_rem_magnitudes(&self.magnitude, &other.magnitude);
+        (
+            Self::new(self.negative != other.negative, quotient),
+            Self::new(self.negative, remainder),
+        )
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitudes(&self.magnitude, &other.magnitude),
+            (true, true) => cmp_magnitudes(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    // peels off nine decimal digits at a time
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.magnitude.is_empty() {
+            return write!(f, "0");
+        }
+        let mut magnitude: Vec<u32> = self.magnitude.clone();
+        let mut chunks: Vec<u32> = Vec::new();
+        while !magnitude.is_empty() {
+            chunks.push(div_small(&mut magnitude, 1_000_000_000));
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", chunks.pop().unwrap_or(0))?;
+        for chunk in chunks.iter().rev() {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+fn cmp_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut sum: Vec<u32> = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry: u64 = 0;
+    for i in 0..a.len().max(b.len()) {
+        let t: u64 = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        sum.push(t as u32);
+        carry = t >> 32;
+    }
+    sum.push(carry as u32);
+    sum
+}
+
+// `a` must not be smaller than `b`
+fn sub_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut difference: Vec<u32> = Vec::with_capacity(a.len());
+    let mut borrow: i64 = 0;
+    for (i, digit) in a.iter().enumerate() {
+        let mut t: i64 = *digit as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = (t < 0) as i64;
+        if t < 0 {
+            t += 1 << 32;
+        }
+        difference.push(t as u32);
+    }
+    difference
+}
+
+// `magnitude * scale + value`, in place
+fn mul_small_add(magnitude: &mut Vec<u32>, scale: u32, value: u32) {
+    let mut carry: u64 = value as u64;
+    for digit in magnitude.iter_mut() {
+        let t: u64 = *digit as u64 * scale as u64 + carry;
+        *digit = t as u32;
+        carry = t >> 32;
+    }
+    if carry > 0 {
+        magnitude.push(carry as u32);
+    }
+}
+
+// divides in place and returns the remainder
+fn div_small(magnitude: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut remainder: u64 = 0;
+    for digit in magnitude.iter_mut().rev() {
+        let t: u64 = (remainder << 32) | *digit as u64;
+        *digit = (t / divisor as u64) as u32;
+        remainder = t % divisor as u64;
+    }
+    while magnitude.last() == Some(&0) {
+        magnitude.pop();
+    }
+    remainder as u32
+}
+
+// long division one bit at a time; `b` must not be zero
+fn div_rem_magnitudes(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if let [divisor] = b {
+        let mut quotient: Vec<u32> = a.to_vec();
+        let remainder: u32 = div_small(&mut quotient, *divisor);
+        return (quotient, vec![remainder]);
+    }
+    let mut quotient: Vec<u32> = vec![0; a.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+    for i in (0..a.len() * 32).rev() {
+        // remainder = remainder * 2 + bit i of a
+        let bit: u32 = (a[i / 32] >> (i % 32)) & 1;
+        mul_small_add(&mut remainder, 2, bit);
+        if cmp_magnitudes(&remainder, b) != Ordering::Less {
+            remainder = sub_magnitudes(&remainder, b);
+            while remainder.last() == Some(&0) {
+                remainder.pop();
+            }
+            quotient[i / 32] |= 1 << (i % 32);
+        }
+    }
+    (quotient, remainder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(n: i128) -> TinyInt {
+        let abs: TinyInt = TinyInt::parse(&n.unsigned_abs().to_string(), 10).unwrap();
+        if n < 0 { -&abs } else { abs }
+    }
+
+    fn big(digits: &str) -> TinyInt {
+        match digits.strip_prefix('-') {
+            Some(digits) => -&TinyInt::parse(digits, 10).unwrap(),
+            None => TinyInt::parse(digits, 10).unwrap(),
+        }
+    }
+
+    // checks the result against i128 and that a value fitting in i64 is always Small
+    fn assert_int(actual: TinyInt, expected: i128) {
+        assert_eq!(actual.to_string(), expected.to_string());
+        assert_eq!(actual.to_i64(), i64::try_from(expected).ok());
+    }
+
+    // values around the i64 and the 32-bit digit boundaries, with both signs
+    const BOUNDARY: [i128; 14] = [
+        0,
+        1,
+        7,
+        1 << 31,
+        (1 << 32) - 1,
+        1 << 32,
+        i64::MAX as i128 - 1,
+        i64::MAX as i128,
+        i64::MAX as i128 + 1,
+        i64::MAX as i128 + 2,
+        (1 << 64) - 1,
+        1 << 64,
+        (1 << 64) + 12_345,
+        1 << 95,
+    ];
+
+    fn operands() -> Vec<(i128, i128)> {
+        let values: Vec<i128> = BOUNDARY.iter().flat_map(|n| [*n, -*n]).collect();
+        values
+            .iter()
+            .flat_map(|a| values.iter().map(move |b| (*a, *b)))
+            .collect()
+    }
+
+    #[test]
+    fn add_and_sub_across_i64_boundary() {
+        for (a, b) in operands() {
+            assert_int(&int(a) + &int(b), a + b);
+            assert_int(&int(a) - &int(b), a - b);
+        }
+    }
+
+    #[test]
+    fn mul_across_i64_boundary() {
+        for (a, b) in operands() {
+            if let Some(product) = a.checked_mul(b) {
+                assert_int(&int(a) * &int(b), product);
+            }
+        }
+    }
+
+    #[test]
+    fn div_and_rem_truncate_like_i64() {
+        for (a, b) in operands() {
+            if b == 0 {
+                assert!(int(a).checked_div(&int(b)).is_none());
+                assert!(int(a).checked_rem(&int(b)).is_none());
+                continue;
+            }
+            assert_int(int(a).checked_div(&int(b)).unwrap(), a / b);
+            assert_int(int(a).checked_rem(&int(b)).unwrap(), a % b);
+        }
+    }
+
+    #[test]
+    fn neg_of_i64_min_is_promoted() {
+        assert_int(-&int(i64::MIN as i128), -(i64::MIN as i128));
+        assert_int(-&int(-(i64::MIN as i128)), i64::MIN as i128);
+    }
+
+    #[test]
+    fn factorial_sized_numbers() {
+        let fact = |n: i64| (1..=n).fold(TinyInt::from(1), |acc, k| &acc * &TinyInt::from(k));
+        let fact_50: TinyInt = fact(50);
+        assert_eq!(
+            fact_50,
+            big("30414093201713378043612608166064768844377641568960512000000000000")
+        );
+        assert_eq!(fact_50.checked_div(&fact(48)), Some(TinyInt::from(2450)));
+        assert_eq!(fact_50.checked_rem(&fact(48)), Some(TinyInt::from(0)));
+
+        let dividend: TinyInt = &fact_50 + &TinyInt::from(7);
+        let divisor: TinyInt = -&fact(25);
+        assert_eq!(
+            dividend.checked_div(&divisor),
+            Some(big("-1960781468160819415703172080467968000000"))
+        );
+        assert_eq!(dividend.checked_rem(&divisor), Some(TinyInt::from(7)));
+        assert_eq!(&(&fact_50 - &fact_50) + &TinyInt::from(1), TinyInt::from(1));
+    }
+}
@@ -1,6 +1,37 @@
+use super::tiny_decimal::TinyDecimal;
+use super::tiny_int::TinyInt;
+use std::rc::Rc;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum TinyObject {
-    Int(i32),
+    Int(TinyInt),
     Bool(bool),
-    Float(f32),
+    Float(f64),
+    Decimal(TinyDecimal),
+    String(Rc<str>),
+    List(Rc<Vec<TinyObject>>),
+    Tuple(Rc<Vec<TinyObject>>),
+    Record(Rc<Vec<TinyObject>>),
+    Variant(Rc<Variant>),
+    Closure(Rc<Closure>),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub entry: usize,
+    pub arity: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Variant {
+    pub name: Rc<str>,
+    pub tag: usize,
+    pub fields: Vec<TinyObject>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Closure {
+    pub function: Rc<Function>,
+    pub upvalues: Vec<TinyObject>,
 }
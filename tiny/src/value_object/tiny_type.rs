@@ -1,6 +1,75 @@
-#[derive(PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum TinyType {
     Int,
     Float,
+    Decimal,
     Bool,
+    String,
+    List(Box<TinyType>),
+    Tuple(Vec<TinyType>),
+    // fields are sorted by name; the position of a field is its offset
+    Record(Vec<(String, TinyType)>),
+    Function {
+        params: Vec<TinyType>,
+        ret: Box<TinyType>,
+    },
+    // user-declared sum type, referred to by name and applied to its type arguments
+    Adt {
+        name: String,
+        args: Vec<TinyType>,
+    },
+    // type parameter of a generic function, which only unifies with itself inside the body;
+    // `id` is a type variable that the parameter becomes once the function is generalized
+    Param {
+        name: String,
+        id: usize,
+    },
+    Var(usize),
+}
+
+pub fn type_to_string(t: TinyType) -> String {
+    match t {
+        TinyType::Int => String::from("Int"),
+        TinyType::Float => String::from("Float"),
+        TinyType::Decimal => String::from("Decimal"),
+        TinyType::Bool => String::from("Bool"),
+        TinyType::String => String::from("String"),
+        TinyType::List(element) => format!("List<{}>", type_to_string(*element)),
+        TinyType::Tuple(elements) => format!(
+            "({})",
+            elements
+                .into_iter()
+                .map(type_to_string)
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        TinyType::Record(fields) => format!(
+            "{{ {} }}",
+            fields
+                .into_iter()
+                .map(|(name, t)| format!("{}: {}", name, type_to_string(t)))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        TinyType::Function { params, ret } => format!(
+            "fn({}) -> {}",
+            params
+                .into_iter()
+                .map(type_to_string)
+                .collect::<Vec<String>>()
+                .join(", "),
+            type_to_string(*ret)
+        ),
+        TinyType::Adt { name, args } if args.is_empty() => name,
+        TinyType::Adt { name, args } => format!(
+            "{}<{}>",
+            name,
+            args.into_iter()
+                .map(type_to_string)
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        TinyType::Param { name, .. } => name,
+        TinyType::Var(id) => format!("'t{}", id),
+    }
 }
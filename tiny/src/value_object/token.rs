@@ -1,24 +1,117 @@
+use super::tiny_decimal::TinyDecimal;
+use super::tiny_int::TinyInt;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
+    KeywordAndAnd,
+    KeywordArrow,
+    KeywordAs,
+    KeywordBreak,
+    KeywordCatch,
+    KeywordContinue,
+    KeywordBang,
     KeywordColon,
+    KeywordComma,
+    KeywordDot,
+    KeywordDotDot,
+    KeywordDoubleEqual,
+    KeywordEqual,
+    KeywordFatArrow,
+    KeywordFn,
+    KeywordGreater,
+    KeywordGreaterEqual,
+    KeywordIn,
+    KeywordLen,
+    KeywordLess,
+    KeywordLessEqual,
+    KeywordLet,
+    KeywordMatch,
+    KeywordMinus,
+    KeywordNotEqual,
+    KeywordOrOr,
+    KeywordPercent,
+    KeywordPipe,
     KeywordPlus,
     KeywordQuestion,
+    KeywordDoubleQuestion,
+    KeywordSemicolon,
+    KeywordSlash,
+    KeywordStar,
+    KeywordThrow,
+    KeywordTry,
+    KeywordType,
+    KeywordVar,
+    KeywordWhile,
+    KeywordWith,
+    BraceLeft,
+    BraceRight,
+    BracketLeft,
+    BracketRight,
     ParenLeft,
     ParenRight,
-    LiteralInt(i32),
-    LiteralFloat(f32),
+    Newline,
+    Identifier(String),
+    LiteralInt(TinyInt),
+    LiteralFloat(f64),
+    LiteralDecimal(TinyDecimal),
     LiteralBool(bool),
+    LiteralString(String),
 }
 
 pub fn token_to_string(t: Token) -> String {
     match t {
+        Token::KeywordAndAnd => String::from("&&"),
+        Token::KeywordArrow => String::from("->"),
+        Token::KeywordAs => String::from("as"),
+        Token::KeywordBreak => String::from("break"),
+        Token::KeywordCatch => String::from("catch"),
+        Token::KeywordContinue => String::from("continue"),
+        Token::KeywordBang => String::from("!"),
         Token::KeywordColon => String::from(":"),
+        Token::KeywordComma => String::from(","),
+        Token::KeywordDot => String::from("."),
+        Token::KeywordDotDot => String::from(".."),
+        Token::KeywordDoubleEqual => String::from("=="),
+        Token::KeywordEqual => String::from("="),
+        Token::KeywordFatArrow => String::from("=>"),
+        Token::KeywordFn => String::from("fn"),
+        Token::KeywordGreater => String::from(">"),
+        Token::KeywordGreaterEqual => String::from(">="),
+        Token::KeywordIn => String::from("in"),
+        Token::KeywordLen => String::from("len"),
+        Token::KeywordLess => String::from("<"),
+        Token::KeywordLessEqual => String::from("<="),
+        Token::KeywordLet => String::from("let"),
+        Token::KeywordMatch => String::from("match"),
+        Token::KeywordMinus => String::from("-"),
+        Token::KeywordNotEqual => String::from("!="),
+        Token::KeywordOrOr => String::from("||"),
+        Token::KeywordPercent => String::from("%"),
+        Token::KeywordPipe => String::from("|"),
         Token::KeywordPlus => String::from("+"),
         Token::KeywordQuestion => String::from("?"),
+        Token::KeywordDoubleQuestion => String::from("??"),
+        Token::KeywordSemicolon => String::from(";"),
+        Token::KeywordSlash => String::from("/"),
+        Token::KeywordStar => String::from("*"),
+        Token::KeywordType => String::from("type"),
+        Token::KeywordVar => String::from("var"),
+        Token::KeywordWhile => String::from("while"),
+        Token::KeywordThrow => String::from("throw"),
+        Token::KeywordTry => String::from("try"),
+        Token::KeywordWith => String::from("with"),
+        Token::BraceLeft => String::from("{"),
+        Token::BraceRight => String::from("}"),
+        Token::BracketLeft => String::from("["),
+        Token::BracketRight => String::from("]"),
         Token::ParenLeft => String::from("("),
         Token::ParenRight => String::from(")"),
+        Token::Newline => String::from("\\n"),
+        Token::Identifier(name) => name,
         Token::LiteralInt(i) => format!("{}", i),
         Token::LiteralFloat(f) => format!("{}", f),
+        Token::LiteralDecimal(d) => format!("{}d", d),
         Token::LiteralBool(b) => format!("{}", b),
+        Token::LiteralString(s) => format!("{:?}", s),
     }
 }
@@ -1,11 +1,48 @@
-use crate::value_object::opcode::OpCode;
-use crate::value_object::tiny_object::TinyObject;
+use crate::value_object::opcode::{Capture, OpCode};
+use crate::value_object::tiny_decimal::{Rounding, TinyDecimal};
+use crate::value_object::tiny_int::TinyInt;
+use crate::value_object::tiny_object::{Closure, TinyObject, Variant};
+use std::cmp::Ordering;
+use std::rc::Rc;
 
 #[derive(Debug)]
 pub enum RuntimeError {
     StackUnderflow,
     InvalidJump,
+    InvalidLocal(usize),
+    InvalidGlobal(usize),
+    InvalidUpvalue(usize),
+    ArityMismatch {
+        name: String,
+        expected: usize,
+        actual: usize,
+    },
     InvalidOperation(String),
+    DivisionByZero,
+    StackOverflow(usize),
+    BreakOutsideLoop,
+    IndexOutOfBounds {
+        index: TinyInt,
+        len: usize,
+    },
+    SliceOutOfBounds {
+        start: TinyInt,
+        end: TinyInt,
+        len: usize,
+    },
+    // only raised when the VM is configured to trap on overflow
+    IntegerOverflow {
+        operation: &'static str,
+        operands: Vec<TinyInt>,
+    },
+    LiteralOverflow(TinyInt),
+    // a NaN or an infinity converted with `as Int` or `as Decimal`
+    InvalidConversion {
+        value: f64,
+        target: &'static str,
+    },
+    // an error value raised by `throw` that no `try` caught
+    Uncaught(TinyObject),
 }
 
 pub fn runtime_error_to_message(e: RuntimeError) -> String {
@@ -14,89 +51,932 @@ pub fn runtime_error_to_message(e: RuntimeError) -> String {
             "Stack Underflow: not enough values on the stack".to_string()
         }
         RuntimeError::InvalidJump => "Invalid Jump: jump target is out of bounds".to_string(),
+        RuntimeError::InvalidLocal(slot) => {
+            format!("Invalid Local: slot {} has not been initialized", slot)
+        }
+        RuntimeError::InvalidGlobal(slot) => {
+            format!("Invalid Global: slot {} has not been initialized", slot)
+        }
+        RuntimeError::InvalidUpvalue(index) => {
+            format!("Invalid Upvalue: index {} is not captured", index)
+        }
+        RuntimeError::ArityMismatch {
+            name,
+            expected,
+            actual,
+        } => format!(
+            "Arity Mismatch: {} expects {} argument(s), but {} were given",
+            name, expected, actual
+        ),
         RuntimeError::InvalidOperation(msg) => {
             format!("Invalid Operation: {}", msg)
         }
+        RuntimeError::DivisionByZero => "Division By Zero: divisor must not be zero".to_string(),
+        RuntimeError::StackOverflow(depth) => {
+            format!("Stack Overflow: call depth exceeded {}", depth)
+        }
+        RuntimeError::BreakOutsideLoop => {
+            "Break Outside Loop: break or continue must be inside a loop".to_string()
+        }
+        RuntimeError::IndexOutOfBounds { index, len } => format!(
+            "Index Out Of Bounds: index {} is out of range for length {}",
+            index, len
+        ),
+        RuntimeError::SliceOutOfBounds { start, end, len } => format!(
+            "Slice Out Of Bounds: range {}..{} is out of range for length {}",
+            start, end, len
+        ),
+        RuntimeError::IntegerOverflow {
+            operation,
+            operands,
+        } => {
+            let expr: String = match operands.as_slice() {
+                [operand] => format!("{}({})", operation, operand),
+                [left, right] => format!("{} {} {}", left, operation, right),
+                _ => operation.to_string(),
+            };
+            format!(
+                "Integer Overflow: result of {} does not fit in 64 bits",
+                expr
+            )
+        }
+        RuntimeError::LiteralOverflow(n) => {
+            format!("Integer Overflow: literal {} does not fit in 64 bits", n)
+        }
+        RuntimeError::InvalidConversion { value, target } => format!(
+            "Invalid Conversion: {} can not be converted to {}",
+            value, target
+        ),
+        RuntimeError::Uncaught(error) => match error {
+            TinyObject::Record(fields) => match fields.as_slice() {
+                [TinyObject::String(kind), TinyObject::String(message)] => {
+                    format!("Uncaught Error: {}: {}", kind, message)
+                }
+                _ => format!("Uncaught Error: {:?}", fields),
+            },
+            other => format!("Uncaught Error: {:?}", other),
+        },
     }
 }
 
+// name of the errors that a `try` can catch; the others are faults of the VM or the compiler
+fn error_kind(e: &RuntimeError) -> Option<&'static str> {
+    match e {
+        RuntimeError::DivisionByZero => Some("DivisionByZero"),
+        RuntimeError::StackOverflow(_) => Some("StackOverflow"),
+        RuntimeError::IndexOutOfBounds { .. } => Some("IndexOutOfBounds"),
+        RuntimeError::SliceOutOfBounds { .. } => Some("SliceOutOfBounds"),
+        RuntimeError::IntegerOverflow { .. } | RuntimeError::LiteralOverflow(_) => {
+            Some("IntegerOverflow")
+        }
+        RuntimeError::InvalidConversion { .. } => Some("InvalidConversion"),
+        _ => None,
+    }
+}
+
+struct Frame {
+    return_pc: usize,
+    locals: Vec<TinyObject>,
+    // the running closure; None for the top-level program
+    closure: Option<Rc<Closure>>,
+    // stack height at the start of each loop being run, innermost last
+    loops: Vec<usize>,
+}
+
+// where to resume when an error is raised inside a `try` body
+struct Handler {
+    pc: usize,
+    frames: usize,
+    stack: usize,
+    loops: usize,
+}
+
+// what an Int operation does with a result that does not fit in 64 bits
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Overflow {
+    // keep the exact result as a big integer
+    #[default]
+    Promote,
+    // raise `RuntimeError::IntegerOverflow`
+    Trap,
+    // two's-complement wrapping
+    Wrap,
+    // clamp to the nearest 64-bit value
+    Saturate,
+}
+
+pub fn overflow_from_name(name: &str) -> Option<Overflow> {
+    match name {
+        "promote" => Some(Overflow::Promote),
+        "trap" => Some(Overflow::Trap),
+        "wrap" => Some(Overflow::Wrap),
+        "saturate" => Some(Overflow::Saturate),
+        _ => None,
+    }
+}
+
+pub fn rounding_from_name(name: &str) -> Option<Rounding> {
+    match name {
+        "half-even" => Some(Rounding::HalfEven),
+        "half-up" => Some(Rounding::HalfUp),
+        "down" => Some(Rounding::Down),
+        "up" => Some(Rounding::Up),
+        "floor" => Some(Rounding::Floor),
+        "ceiling" => Some(Rounding::Ceiling),
+        _ => None,
+    }
+}
+
+// keeps `*` and `/` on Decimals fast, since both scale their operands by 10^scale
+pub const MAX_DECIMAL_SCALE: u32 = 1000;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    pub overflow: Overflow,
+    // the most fractional digits that `*`, `/` and `as Decimal` keep
+    pub decimal_scale: u32,
+    pub rounding: Rounding,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            overflow: Overflow::default(),
+            decimal_scale: 20,
+            rounding: Rounding::default(),
+        }
+    }
+}
+
+// deepest chain of calls that are not tail calls
+const MAX_FRAMES: usize = 100_000;
+
 pub struct VM {
     pub stack: Vec<TinyObject>,
+    // the bottom frame belongs to the top-level program and is never popped
+    frames: Vec<Frame>,
+    // innermost last
+    handlers: Vec<Handler>,
+    globals: Vec<TinyObject>,
     pc: usize,
     code: Vec<OpCode>,
+    config: Config,
 }
 
 impl VM {
-    pub fn new(code: Vec<OpCode>) -> Self {
+    pub fn new(code: Vec<OpCode>, config: Config) -> Self {
         Self {
             stack: Vec::new(),
+            frames: vec![Frame {
+                return_pc: 0,
+                locals: Vec::new(),
+                closure: None,
+                loops: Vec::new(),
+            }],
+            handlers: Vec::new(),
+            globals: Vec::new(),
             pc: 0,
             code,
+            config,
         }
     }
 
     pub fn run(&mut self) -> Result<Option<TinyObject>, RuntimeError> {
         while self.pc < self.code.len() {
-            match self.code[self.pc].clone() {
-                OpCode::Push(obj) => {
-                    self.stack.push(obj);
-                    self.pc += 1;
+            if let Err(e) = self.step() {
+                self.unwind(e)?;
+            }
+        }
+
+        Ok(self.stack.last().cloned())
+    }
+
+    // resumes at the innermost handler with the error as a value, or gives the error back
+    fn unwind(&mut self, e: RuntimeError) -> Result<(), RuntimeError> {
+        let Some(handler) = self.handlers.pop() else {
+            return Err(e);
+        };
+        let error: TinyObject = match e {
+            RuntimeError::Uncaught(error) => error,
+            e => match error_kind(&e) {
+                Some(kind) => TinyObject::Record(Rc::new(vec![
+                    TinyObject::String(Rc::from(kind)),
+                    TinyObject::String(Rc::from(runtime_error_to_message(e))),
+                ])),
+                None => return Err(e),
+            },
+        };
+        self.frames.truncate(handler.frames);
+        self.frame().loops.truncate(handler.loops);
+        self.stack.truncate(handler.stack);
+        self.stack.push(error);
+        self.pc = handler.pc;
+        Ok(())
+    }
+
+    fn step(&mut self) -> Result<(), RuntimeError> {
+        match self.code[self.pc].clone() {
+            // a literal outside i64 follows the overflow behavior like a computed Int
+            OpCode::Push(TinyObject::Int(n)) if n.to_i64().is_none() => {
+                let n: TinyInt = match self.config.overflow {
+                    Overflow::Trap => return Err(RuntimeError::LiteralOverflow(n)),
+                    _ => self.fit(n, "", Vec::new())?,
+                };
+                self.stack.push(TinyObject::Int(n));
+                self.pc += 1;
+            }
+            OpCode::Push(obj) => {
+                self.stack.push(obj);
+                self.pc += 1;
+            }
+            OpCode::Add
+                if matches!(
+                    self.stack.as_slice(),
+                    [.., TinyObject::String(_), TinyObject::String(_)]
+                ) =>
+            {
+                let (a, b) = self.pop_pair()?;
+                if let (TinyObject::String(a), TinyObject::String(b)) = (a, b) {
+                    self.stack
+                        .push(TinyObject::String(Rc::from([&*a, &*b].concat())));
                 }
-                OpCode::Add => {
-                    let b = self.stack.pop().ok_or(RuntimeError::StackUnderflow)?;
-                    let a = self.stack.pop().ok_or(RuntimeError::StackUnderflow)?;
-                    match (a, b) {
-                        (TinyObject::Int(a), TinyObject::Int(b)) => {
-                            self.stack.push(TinyObject::Int(a + b));
-                        }
-                        (TinyObject::Float(a), TinyObject::Int(b)) => {
-                            self.stack.push(TinyObject::Float(a + b as f32));
+                self.pc += 1;
+            }
+            OpCode::Add => {
+                self.arithmetic(
+                    "Add",
+                    "+",
+                    |a, b| Ok(a + b),
+                    |a, b| Ok(a + b),
+                    |a, b, _| Ok(a.add(b)),
+                )?;
+                self.pc += 1;
+            }
+            OpCode::Sub => {
+                self.arithmetic(
+                    "Sub",
+                    "-",
+                    |a, b| Ok(a - b),
+                    |a, b| Ok(a - b),
+                    |a, b, _| Ok(a.sub(b)),
+                )?;
+                self.pc += 1;
+            }
+            OpCode::Mul => {
+                self.arithmetic(
+                    "Mul",
+                    "*",
+                    |a, b| Ok(a * b),
+                    |a, b| Ok(a * b),
+                    |a, b, config| Ok(a.mul(b, config.decimal_scale, config.rounding)),
+                )?;
+                self.pc += 1;
+            }
+            OpCode::Div => {
+                self.arithmetic(
+                    "Div",
+                    "/",
+                    |a, b| a.checked_div(b).ok_or(RuntimeError::DivisionByZero),
+                    |a, b| match b {
+                        0.0 => Err(RuntimeError::DivisionByZero),
+                        _ => Ok(a / b),
+                    },
+                    |a, b, config| {
+                        a.div(b, config.decimal_scale, config.rounding)
+                            .ok_or(RuntimeError::DivisionByZero)
+                    },
+                )?;
+                self.pc += 1;
+            }
+            OpCode::Mod => {
+                self.arithmetic(
+                    "Mod",
+                    "%",
+                    |a, b| a.checked_rem(b).ok_or(RuntimeError::DivisionByZero),
+                    |a, b| match b {
+                        0.0 => Err(RuntimeError::DivisionByZero),
+                        _ => Ok(a % b),
+                    },
+                    |a, b, _| a.rem(b).ok_or(RuntimeError::DivisionByZero),
+                )?;
+                self.pc += 1;
+            }
+            OpCode::Equal => {
+                let (a, b) = self.pop_pair()?;
+                self.stack.push(TinyObject::Bool(Self::equals(&a, &b)));
+                self.pc += 1;
+            }
+            OpCode::NotEqual => {
+                let (a, b) = self.pop_pair()?;
+                self.stack.push(TinyObject::Bool(!Self::equals(&a, &b)));
+                self.pc += 1;
+            }
+            OpCode::Less => {
+                self.compare("Less", Ordering::is_lt)?;
+                self.pc += 1;
+            }
+            OpCode::LessEqual => {
+                self.compare("LessEqual", Ordering::is_le)?;
+                self.pc += 1;
+            }
+            OpCode::Greater => {
+                self.compare("Greater", Ordering::is_gt)?;
+                self.pc += 1;
+            }
+            OpCode::GreaterEqual => {
+                self.compare("GreaterEqual", Ordering::is_ge)?;
+                self.pc += 1;
+            }
+            OpCode::MakeList(n) => {
+                if self.stack.len() < n {
+                    return Err(RuntimeError::StackUnderflow);
+                }
+                let elements: Vec<TinyObject> = self.stack.split_off(self.stack.len() - n);
+                self.stack.push(TinyObject::List(Rc::new(elements)));
+                self.pc += 1;
+            }
+            OpCode::MakeTuple(n) => {
+                if self.stack.len() < n {
+                    return Err(RuntimeError::StackUnderflow);
+                }
+                let elements: Vec<TinyObject> = self.stack.split_off(self.stack.len() - n);
+                self.stack.push(TinyObject::Tuple(Rc::new(elements)));
+                self.pc += 1;
+            }
+            OpCode::TupleField(index) => {
+                let value: TinyObject =
+                    match self.stack.pop().ok_or(RuntimeError::StackUnderflow)? {
+                        TinyObject::Tuple(elements) if index < elements.len() => {
+                            elements[index].clone()
                         }
-                        (TinyObject::Int(a), TinyObject::Float(b)) => {
-                            self.stack.push(TinyObject::Float(a as f32 + b));
+                        other => {
+                            return Err(RuntimeError::InvalidOperation(format!(
+                                "Execute the TupleField operation for undefined type. {:?}",
+                                other
+                            )));
                         }
-                        (TinyObject::Float(a), TinyObject::Float(b)) => {
-                            self.stack.push(TinyObject::Float(a + b));
+                    };
+                self.stack.push(value);
+                self.pc += 1;
+            }
+            OpCode::Index => {
+                let (list, index) = self.pop_pair()?;
+                let (list, index) = match (list, index) {
+                    (TinyObject::List(list), TinyObject::Int(index)) => (list, index),
+                    (a, b) => {
+                        return Err(RuntimeError::InvalidOperation(format!(
+                            "Execute the Index operation for undefined type combinations. {:?} {:?}",
+                            a, b
+                        )));
+                    }
+                };
+                let element: TinyObject =
+                    index.to_usize().and_then(|i| list.get(i)).cloned().ok_or(
+                        RuntimeError::IndexOutOfBounds {
+                            index,
+                            len: list.len(),
+                        },
+                    )?;
+                self.stack.push(element);
+                self.pc += 1;
+            }
+            OpCode::Slice => {
+                let end: TinyObject = self.stack.pop().ok_or(RuntimeError::StackUnderflow)?;
+                let (list, start) = self.pop_pair()?;
+                let (list, start, end) = match (list, start, end) {
+                    (TinyObject::List(list), TinyObject::Int(start), TinyObject::Int(end)) => {
+                        (list, start, end)
+                    }
+                    (a, b, c) => {
+                        return Err(RuntimeError::InvalidOperation(format!(
+                            "Execute the Slice operation for undefined type combinations. {:?} {:?} {:?}",
+                            a, b, c
+                        )));
+                    }
+                };
+                let elements: &[TinyObject] = start
+                    .to_usize()
+                    .zip(end.to_usize())
+                    .and_then(|(s, e)| list.get(s..e))
+                    .ok_or(RuntimeError::SliceOutOfBounds {
+                        start,
+                        end,
+                        len: list.len(),
+                    })?;
+                self.stack
+                    .push(TinyObject::List(Rc::new(elements.to_vec())));
+                self.pc += 1;
+            }
+            OpCode::Len => {
+                let len: usize = match self.stack.pop().ok_or(RuntimeError::StackUnderflow)? {
+                    TinyObject::List(list) => list.len(),
+                    TinyObject::String(s) => s.chars().count(),
+                    other => {
+                        return Err(RuntimeError::InvalidOperation(format!(
+                            "Execute the Len operation for undefined type. {:?}",
+                            other
+                        )));
+                    }
+                };
+                // a length always fits in i64
+                self.stack.push(TinyObject::Int(TinyInt::from(len as i64)));
+                self.pc += 1;
+            }
+            OpCode::MakeRecord(offsets) => {
+                if self.stack.len() < offsets.len() {
+                    return Err(RuntimeError::StackUnderflow);
+                }
+                let values: Vec<TinyObject> =
+                    self.stack.split_off(self.stack.len() - offsets.len());
+                let mut fields: Vec<(usize, TinyObject)> =
+                    offsets.into_iter().zip(values).collect();
+                fields.sort_by_key(|(offset, _)| *offset);
+                let fields: Vec<TinyObject> = fields.into_iter().map(|(_, v)| v).collect();
+                self.stack.push(TinyObject::Record(Rc::new(fields)));
+                self.pc += 1;
+            }
+            OpCode::GetField(offset) => {
+                let value: TinyObject =
+                    match self.stack.pop().ok_or(RuntimeError::StackUnderflow)? {
+                        TinyObject::Record(fields) if offset < fields.len() => {
+                            fields[offset].clone()
                         }
-                        (a, b) => {
+                        other => {
                             return Err(RuntimeError::InvalidOperation(format!(
-                                "Execute the Add operation for undefined type combinations. {:?} {:?}",
-                                a, b
+                                "Execute the GetField operation for undefined type. {:?}",
+                                other
                             )));
                         }
+                    };
+                self.stack.push(value);
+                self.pc += 1;
+            }
+            OpCode::SetField(offset) => {
+                let (record, value) = self.pop_pair()?;
+                let fields: Vec<TinyObject> = match record {
+                    TinyObject::Record(fields) if offset < fields.len() => {
+                        let mut fields: Vec<TinyObject> = fields.as_ref().clone();
+                        fields[offset] = value;
+                        fields
                     }
-                    self.pc += 1;
+                    other => {
+                        return Err(RuntimeError::InvalidOperation(format!(
+                            "Execute the SetField operation for undefined type. {:?}",
+                            other
+                        )));
+                    }
+                };
+                self.stack.push(TinyObject::Record(Rc::new(fields)));
+                self.pc += 1;
+            }
+            OpCode::MakeVariant { name, tag, arity } => {
+                if self.stack.len() < arity {
+                    return Err(RuntimeError::StackUnderflow);
                 }
-                OpCode::JumpIfFalse(target) => {
-                    let cond: TinyObject = self.stack.pop().ok_or(RuntimeError::StackUnderflow)?;
-                    if !Self::evaluate_condition(cond)? {
-                        if target > self.code.len() {
-                            return Err(RuntimeError::InvalidJump);
+                let fields: Vec<TinyObject> = self.stack.split_off(self.stack.len() - arity);
+                self.stack
+                    .push(TinyObject::Variant(Rc::new(Variant { name, tag, fields })));
+                self.pc += 1;
+            }
+            OpCode::IsVariant(tag) => {
+                let is_variant: bool = match self.stack.pop().ok_or(RuntimeError::StackUnderflow)? {
+                    TinyObject::Variant(variant) => variant.tag == tag,
+                    other => {
+                        return Err(RuntimeError::InvalidOperation(format!(
+                            "Execute the IsVariant operation for undefined type. {:?}",
+                            other
+                        )));
+                    }
+                };
+                self.stack.push(TinyObject::Bool(is_variant));
+                self.pc += 1;
+            }
+            OpCode::VariantField(index) => {
+                let value: TinyObject =
+                    match self.stack.pop().ok_or(RuntimeError::StackUnderflow)? {
+                        TinyObject::Variant(variant) if index < variant.fields.len() => {
+                            variant.fields[index].clone()
+                        }
+                        other => {
+                            return Err(RuntimeError::InvalidOperation(format!(
+                                "Execute the VariantField operation for undefined type. {:?}",
+                                other
+                            )));
                         }
-                        self.pc = target;
-                    } else {
-                        self.pc += 1;
+                    };
+                self.stack.push(value);
+                self.pc += 1;
+            }
+            OpCode::ToInt => {
+                let value: TinyObject =
+                    match self.stack.pop().ok_or(RuntimeError::StackUnderflow)? {
+                        TinyObject::Int(n) => TinyObject::Int(n),
+                        // both truncate toward zero
+                        TinyObject::Float(f) => {
+                            let n: TinyInt = TinyDecimal::from_f64(f, 0, Rounding::Down)
+                                .ok_or(RuntimeError::InvalidConversion {
+                                    value: f,
+                                    target: "Int",
+                                })?
+                                .to_int();
+                            TinyObject::Int(self.fit(n.clone(), "Int", vec![n])?)
+                        }
+                        TinyObject::Decimal(d) => {
+                            let n: TinyInt = d.to_int();
+                            TinyObject::Int(self.fit(n.clone(), "Int", vec![n])?)
+                        }
+                        other => {
+                            return Err(RuntimeError::InvalidOperation(format!(
+                                "Execute the ToInt operation for undefined type. {:?}",
+                                other
+                            )));
+                        }
+                    };
+                self.stack.push(value);
+                self.pc += 1;
+            }
+            OpCode::ToDecimal => {
+                let value: TinyObject = match self
+                    .stack
+                    .pop()
+                    .ok_or(RuntimeError::StackUnderflow)?
+                {
+                    TinyObject::Int(n) => TinyObject::Decimal(TinyDecimal::from_int(&n)),
+                    // the shortest digits that read back as `f`, so `0.1` becomes `0.1`
+                    TinyObject::Float(f) => TinyObject::Decimal(
+                        TinyDecimal::from_f64(f, self.config.decimal_scale, self.config.rounding)
+                            .ok_or(RuntimeError::InvalidConversion {
+                            value: f,
+                            target: "Decimal",
+                        })?,
+                    ),
+                    TinyObject::Decimal(d) => TinyObject::Decimal(d),
+                    other => {
+                        return Err(RuntimeError::InvalidOperation(format!(
+                            "Execute the ToDecimal operation for undefined type. {:?}",
+                            other
+                        )));
+                    }
+                };
+                self.stack.push(value);
+                self.pc += 1;
+            }
+            OpCode::ToFloat => {
+                let value: TinyObject =
+                    match self.stack.pop().ok_or(RuntimeError::StackUnderflow)? {
+                        TinyObject::Int(n) => TinyObject::Float(n.to_f64()),
+                        TinyObject::Float(f) => TinyObject::Float(f),
+                        TinyObject::Decimal(d) => TinyObject::Float(d.to_f64()),
+                        other => {
+                            return Err(RuntimeError::InvalidOperation(format!(
+                                "Execute the ToFloat operation for undefined type. {:?}",
+                                other
+                            )));
+                        }
+                    };
+                self.stack.push(value);
+                self.pc += 1;
+            }
+            OpCode::Not => {
+                match self.stack.pop().ok_or(RuntimeError::StackUnderflow)? {
+                    TinyObject::Bool(b) => self.stack.push(TinyObject::Bool(!b)),
+                    other => {
+                        return Err(RuntimeError::InvalidOperation(format!(
+                            "Execute the Not operation for undefined type. {:?}",
+                            other
+                        )));
+                    }
+                }
+                self.pc += 1;
+            }
+            OpCode::Negate => {
+                match self.stack.pop().ok_or(RuntimeError::StackUnderflow)? {
+                    TinyObject::Int(n) => {
+                        let result: TinyInt = self.fit(-&n, "-", vec![n])?;
+                        self.stack.push(TinyObject::Int(result));
+                    }
+                    TinyObject::Float(f) => self.stack.push(TinyObject::Float(-f)),
+                    TinyObject::Decimal(d) => self.stack.push(TinyObject::Decimal(d.neg())),
+                    other => {
+                        return Err(RuntimeError::InvalidOperation(format!(
+                            "Execute the Negate operation for undefined type. {:?}",
+                            other
+                        )));
                     }
                 }
-                OpCode::Jump(target) => {
+                self.pc += 1;
+            }
+            OpCode::JumpIfFalse(target) => {
+                let cond: TinyObject = self.stack.pop().ok_or(RuntimeError::StackUnderflow)?;
+                if !Self::evaluate_condition(cond)? {
                     if target > self.code.len() {
                         return Err(RuntimeError::InvalidJump);
                     }
                     self.pc = target;
-                }
-                OpCode::Pop => {
-                    self.stack.pop().ok_or(RuntimeError::StackUnderflow)?;
+                } else {
                     self.pc += 1;
                 }
             }
+            OpCode::Jump(target) => {
+                if target > self.code.len() {
+                    return Err(RuntimeError::InvalidJump);
+                }
+                self.pc = target;
+            }
+            OpCode::EnterLoop => {
+                let height: usize = self.stack.len();
+                self.frame().loops.push(height);
+                self.pc += 1;
+            }
+            OpCode::ExitLoop => {
+                self.frame()
+                    .loops
+                    .pop()
+                    .ok_or(RuntimeError::BreakOutsideLoop)?;
+                self.pc += 1;
+            }
+            OpCode::Break(target) | OpCode::Continue(target) => {
+                if target > self.code.len() {
+                    return Err(RuntimeError::InvalidJump);
+                }
+                let height: usize = *self
+                    .frame()
+                    .loops
+                    .last()
+                    .ok_or(RuntimeError::BreakOutsideLoop)?;
+                self.stack.truncate(height);
+                self.pc = target;
+            }
+            OpCode::LoadLocal(slot) => {
+                let value: TinyObject = self
+                    .frame()
+                    .locals
+                    .get(slot)
+                    .cloned()
+                    .ok_or(RuntimeError::InvalidLocal(slot))?;
+                self.stack.push(value);
+                self.pc += 1;
+            }
+            OpCode::StoreLocal(slot) => {
+                let value: TinyObject = self.stack.pop().ok_or(RuntimeError::StackUnderflow)?;
+                if !Self::store(&mut self.frame().locals, slot, value) {
+                    return Err(RuntimeError::InvalidLocal(slot));
+                }
+                self.pc += 1;
+            }
+            OpCode::LoadUpvalue(index) => {
+                let value: TinyObject = self.capture(Capture::Upvalue(index))?;
+                self.stack.push(value);
+                self.pc += 1;
+            }
+            OpCode::MakeClosure { function, captures } => {
+                let upvalues: Vec<TinyObject> = captures
+                    .iter()
+                    .map(|capture| self.capture(*capture))
+                    .collect::<Result<Vec<TinyObject>, RuntimeError>>()?;
+                self.stack
+                    .push(TinyObject::Closure(Rc::new(Closure { function, upvalues })));
+                self.pc += 1;
+            }
+            OpCode::LoadGlobal(slot) => {
+                let value: TinyObject = self
+                    .globals
+                    .get(slot)
+                    .cloned()
+                    .ok_or(RuntimeError::InvalidGlobal(slot))?;
+                self.stack.push(value);
+                self.pc += 1;
+            }
+            OpCode::StoreGlobal(slot) => {
+                let value: TinyObject = self.stack.pop().ok_or(RuntimeError::StackUnderflow)?;
+                if !Self::store(&mut self.globals, slot, value) {
+                    return Err(RuntimeError::InvalidGlobal(slot));
+                }
+                self.pc += 1;
+            }
+            OpCode::Call(argc) => {
+                if self.frames.len() >= MAX_FRAMES {
+                    return Err(RuntimeError::StackOverflow(MAX_FRAMES));
+                }
+                let (closure, args) = self.pop_call(argc)?;
+                let entry: usize = closure.function.entry;
+                self.frames.push(Frame {
+                    return_pc: self.pc + 1,
+                    locals: args,
+                    closure: Some(closure),
+                    loops: Vec::new(),
+                });
+                self.pc = entry;
+            }
+            OpCode::TailCall(argc) => {
+                let (closure, args) = self.pop_call(argc)?;
+                let entry: usize = closure.function.entry;
+                // the callee returns straight to the caller of the current frame
+                let frame: &mut Frame = self.frame();
+                frame.locals = args;
+                frame.closure = Some(closure);
+                frame.loops.clear();
+                self.pc = entry;
+            }
+            OpCode::Return => {
+                if self.frames.len() == 1 {
+                    return Err(RuntimeError::InvalidOperation(
+                        "Return outside of a function".to_string(),
+                    ));
+                }
+                let frame: Frame = self.frames.pop().ok_or(RuntimeError::StackUnderflow)?;
+                self.pc = frame.return_pc;
+            }
+            OpCode::Pop => {
+                self.stack.pop().ok_or(RuntimeError::StackUnderflow)?;
+                self.pc += 1;
+            }
+            OpCode::Try(target) => {
+                if target > self.code.len() {
+                    return Err(RuntimeError::InvalidJump);
+                }
+                let loops: usize = self.frame().loops.len();
+                self.handlers.push(Handler {
+                    pc: target,
+                    frames: self.frames.len(),
+                    stack: self.stack.len(),
+                    loops,
+                });
+                self.pc += 1;
+            }
+            OpCode::EndTry => {
+                self.handlers.pop().ok_or_else(|| {
+                    RuntimeError::InvalidOperation("EndTry outside of a try".to_string())
+                })?;
+                self.pc += 1;
+            }
+            OpCode::Throw => {
+                let error: TinyObject = self.stack.pop().ok_or(RuntimeError::StackUnderflow)?;
+                return Err(RuntimeError::Uncaught(error));
+            }
         }
+        Ok(())
+    }
 
-        Ok(self.stack.last().cloned())
+    // pops two operands and pushes the result; an Int mixed with a Float is promoted to Float,
+    // while a Decimal only meets another Decimal
+    fn arithmetic(
+        &mut self,
+        name: &str,
+        symbol: &'static str,
+        int_op: fn(&TinyInt, &TinyInt) -> Result<TinyInt, RuntimeError>,
+        float_op: fn(f64, f64) -> Result<f64, RuntimeError>,
+        decimal_op: fn(&TinyDecimal, &TinyDecimal, &Config) -> Result<TinyDecimal, RuntimeError>,
+    ) -> Result<(), RuntimeError> {
+        let (a, b) = self.pop_pair()?;
+        let result: TinyObject = match (a, b) {
+            (TinyObject::Int(a), TinyObject::Int(b)) => {
+                let result: TinyInt = int_op(&a, &b)?;
+                TinyObject::Int(self.fit(result, symbol, vec![a, b])?)
+            }
+            (TinyObject::Float(a), TinyObject::Int(b)) => {
+                TinyObject::Float(float_op(a, b.to_f64())?)
+            }
+            (TinyObject::Int(a), TinyObject::Float(b)) => {
+                TinyObject::Float(float_op(a.to_f64(), b)?)
+            }
+            (TinyObject::Float(a), TinyObject::Float(b)) => TinyObject::Float(float_op(a, b)?),
+            (TinyObject::Decimal(a), TinyObject::Decimal(b)) => {
+                TinyObject::Decimal(decimal_op(&a, &b, &self.config)?)
+            }
+            (a, b) => {
+                return Err(RuntimeError::InvalidOperation(format!(
+                    "Execute the {} operation for undefined type combinations. {:?} {:?}",
+                    name, a, b
+                )));
+            }
+        };
+        self.stack.push(result);
+        Ok(())
+    }
+
+    // applies the configured overflow behavior to the exact result of an Int operation
+    fn fit(
+        &self,
+        result: TinyInt,
+        operation: &'static str,
+        operands: Vec<TinyInt>,
+    ) -> Result<TinyInt, RuntimeError> {
+        if result.to_i64().is_some() {
+            return Ok(result);
+        }
+        match self.config.overflow {
+            Overflow::Promote => Ok(result),
+            Overflow::Trap => Err(RuntimeError::IntegerOverflow {
+                operation,
+                operands,
+            }),
+            Overflow::Wrap => Ok(TinyInt::from(result.wrapping_to_i64())),
+            Overflow::Saturate => Ok(TinyInt::from(result.saturating_to_i64())),
+        }
+    }
+
+    // pops two numeric operands and pushes whether their ordering is accepted
+    fn compare(&mut self, name: &str, accept: fn(Ordering) -> bool) -> Result<(), RuntimeError> {
+        let (a, b) = self.pop_pair()?;
+        let ordering: Option<Ordering> = match (&a, &b) {
+            (TinyObject::Int(a), TinyObject::Int(b)) => Some(a.cmp(b)),
+            (TinyObject::Float(a), TinyObject::Int(b)) => a.partial_cmp(&b.to_f64()),
+            (TinyObject::Int(a), TinyObject::Float(b)) => a.to_f64().partial_cmp(b),
+            (TinyObject::Float(a), TinyObject::Float(b)) => a.partial_cmp(b),
+            (TinyObject::Decimal(a), TinyObject::Decimal(b)) => Some(a.cmp(b)),
+            _ => {
+                return Err(RuntimeError::InvalidOperation(format!(
+                    "Execute the {} operation for undefined type combinations. {:?} {:?}",
+                    name, a, b
+                )));
+            }
+        };
+        // NaN is unordered, so every comparison with it is false
+        self.stack
+            .push(TinyObject::Bool(ordering.is_some_and(accept)));
+        Ok(())
+    }
+
+    fn equals(a: &TinyObject, b: &TinyObject) -> bool {
+        match (a, b) {
+            (TinyObject::Float(a), TinyObject::Int(b)) => *a == b.to_f64(),
+            (TinyObject::Int(a), TinyObject::Float(b)) => a.to_f64() == *b,
+            _ => a == b,
+        }
+    }
+
+    // pops the callee and its arguments, checking that they can be called
+    fn pop_call(&mut self, argc: usize) -> Result<(Rc<Closure>, Vec<TinyObject>), RuntimeError> {
+        if self.stack.len() < argc + 1 {
+            return Err(RuntimeError::StackUnderflow);
+        }
+        let args: Vec<TinyObject> = self.stack.split_off(self.stack.len() - argc);
+        let callee: TinyObject = self.stack.pop().ok_or(RuntimeError::StackUnderflow)?;
+        let closure: Rc<Closure> = match callee {
+            TinyObject::Closure(closure) => closure,
+            other => {
+                return Err(RuntimeError::InvalidOperation(format!(
+                    "Value is not callable: {:?}",
+                    other
+                )));
+            }
+        };
+        if closure.function.arity != argc {
+            return Err(RuntimeError::ArityMismatch {
+                name: closure.function.name.clone(),
+                expected: closure.function.arity,
+                actual: argc,
+            });
+        }
+        if closure.function.entry > self.code.len() {
+            return Err(RuntimeError::InvalidJump);
+        }
+        Ok((closure, args))
+    }
+
+    fn pop_pair(&mut self) -> Result<(TinyObject, TinyObject), RuntimeError> {
+        let b = self.stack.pop().ok_or(RuntimeError::StackUnderflow)?;
+        let a = self.stack.pop().ok_or(RuntimeError::StackUnderflow)?;
+        Ok((a, b))
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        self.frames
+            .last_mut()
+            .expect("the top-level frame is never popped")
+    }
+
+    fn capture(&mut self, capture: Capture) -> Result<TinyObject, RuntimeError> {
+        let frame: &Frame = self.frame();
+        match capture {
+            Capture::Local(slot) => frame
+                .locals
+                .get(slot)
+                .cloned()
+                .ok_or(RuntimeError::InvalidLocal(slot)),
+            Capture::Upvalue(index) => frame
+                .closure
+                .as_ref()
+                .and_then(|closure| closure.upvalues.get(index))
+                .cloned()
+                .ok_or(RuntimeError::InvalidUpvalue(index)),
+        }
+    }
+
+    // slots are filled in order, so a store may only overwrite a slot or append the next one
+    fn store(slots: &mut Vec<TinyObject>, slot: usize, value: TinyObject) -> bool {
+        match slot.cmp(&slots.len()) {
+            std::cmp::Ordering::Less => slots[slot] = value,
+            std::cmp::Ordering::Equal => slots.push(value),
+            std::cmp::Ordering::Greater => return false,
+        }
+        true
     }
 
     fn evaluate_condition(obj: TinyObject) -> Result<bool, RuntimeError> {
         match obj {
-            TinyObject::Int(n) => Ok(n > 0),
+            TinyObject::Int(n) => Ok(n.is_positive()),
             TinyObject::Bool(b) => Ok(b),
             _ => Err(RuntimeError::InvalidOperation(
                 format!("Float value can not be used as condition: {:?}", obj).to_string(),
@@ -104,3 +984,300 @@ impl VM {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::{Compiler, compile_error_to_message};
+    use crate::parser::{Parser, parse_error_to_message};
+    use crate::tokenizer::{Tokenizer, tokenize_error_to_message};
+    use crate::typechecker::{TypeChecker, typecheck_error_to_message};
+    use crate::value_object::ast::Stmt;
+
+    // runs `program` through every stage; only the VM may fail
+    fn run_with(program: &str, config: Config) -> Result<Option<TinyObject>, RuntimeError> {
+        let tokens = Tokenizer::tokenize(program)
+            .unwrap_or_else(|e| panic!("{}", tokenize_error_to_message(e)));
+        let mut ast: Vec<Stmt> = Parser::new(tokens)
+            .parse()
+            .unwrap_or_else(|e| panic!("{}", parse_error_to_message(e)));
+        TypeChecker::typecheck(&mut ast)
+            .unwrap_or_else(|e| panic!("{}", typecheck_error_to_message(e)));
+        let code: Vec<OpCode> = Compiler::new()
+            .compile(ast)
+            .unwrap_or_else(|e| panic!("{}", compile_error_to_message(e)));
+        VM::new(code, config).run()
+    }
+
+    fn run(program: &str) -> Result<Option<TinyObject>, RuntimeError> {
+        run_with(program, Config::default())
+    }
+
+    #[test]
+    fn and_skips_right_operand_when_left_is_false() {
+        let result = run("false && (1 / 0 == 1)");
+        assert!(matches!(result, Ok(Some(TinyObject::Bool(false)))));
+    }
+
+    #[test]
+    fn or_skips_right_operand_when_left_is_true() {
+        let result = run("true || (1 / 0 == 1)");
+        assert!(matches!(result, Ok(Some(TinyObject::Bool(true)))));
+    }
+
+    #[test]
+    fn self_tail_call_reuses_frame() {
+        let result = run("fn count(n) = n == 0 ? 0 : count(n - 1); count(10000000)");
+        assert_eq!(result.unwrap(), Some(TinyObject::Int(TinyInt::from(0))));
+    }
+
+    #[test]
+    fn mutual_tail_calls_reuse_frame() {
+        let program: &str = "fn even(n) = n == 0 ? true : odd(n - 1); \
+                             fn odd(n) = n == 0 ? false : even(n - 1); \
+                             even(1000001)";
+        assert_eq!(run(program).unwrap(), Some(TinyObject::Bool(false)));
+    }
+
+    #[test]
+    fn non_tail_recursion_overflows() {
+        let result = run("fn f(n) = n == 0 ? 0 : 1 + f(n - 1); f(1000000)");
+        assert!(matches!(result, Err(RuntimeError::StackOverflow(_))));
+    }
+
+    #[test]
+    fn generic_function_instantiates_at_each_type() {
+        let program: &str = "fn pick<T>(first: Bool, a: T, b: T) -> T = first ? a : b; \
+                             (pick(true, 1, 2), pick(false, 1.5, 2.5), pick(true, false, true))";
+        let expected: TinyObject = TinyObject::Tuple(Rc::new(vec![
+            TinyObject::Int(TinyInt::from(1)),
+            TinyObject::Float(2.5),
+            TinyObject::Bool(false),
+        ]));
+        assert_eq!(run(program).unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn type_parameter_named_float_is_not_widened() {
+        let result = run("fn f<Float>(x: Float) -> Float = x; f(true)");
+        assert_eq!(result.unwrap(), Some(TinyObject::Bool(true)));
+    }
+
+    #[test]
+    fn int_is_widened_wherever_a_float_is_expected() {
+        let float = |f: f64| TinyObject::Float(f);
+        let list = |items: Vec<TinyObject>| TinyObject::List(Rc::new(items));
+        let cases: [(&str, TinyObject); 5] = [
+            ("let x: Float = 1; x", float(1.0)),
+            ("fn f(x: Float) = x; f(1)", float(1.0)),
+            ("fn f() -> Float = 2; f()", float(2.0)),
+            (
+                "let xs: List<Float> = [1, 2]; xs",
+                list(vec![float(1.0), float(2.0)]),
+            ),
+            ("[1, 2.5]", list(vec![float(1.0), float(2.5)])),
+        ];
+        for (program, expected) in cases {
+            assert_eq!(run(program).unwrap(), Some(expected), "{}", program);
+        }
+    }
+
+    #[test]
+    fn match_selects_the_last_arm() {
+        let result =
+            run("type S = A | B(Int) | C(Int); match C(3) { A => 1, B(n) => n, C(n) => n * 10 }");
+        assert_eq!(result.unwrap(), Some(TinyObject::Int(TinyInt::from(30))));
+    }
+
+    #[test]
+    fn default_unwraps_some_and_falls_back_on_none() {
+        let result = run("(Some(2) ?? 0, None ?? 5)");
+        let expected: TinyObject = TinyObject::Tuple(Rc::new(vec![
+            TinyObject::Int(TinyInt::from(2)),
+            TinyObject::Int(TinyInt::from(5)),
+        ]));
+        assert_eq!(result.unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn negative_index_is_out_of_bounds() {
+        let result = run("[1, 2][-1]");
+        assert!(matches!(
+            result,
+            Err(RuntimeError::IndexOutOfBounds { index, len: 2 }) if index == TinyInt::from(-1)
+        ));
+    }
+
+    #[test]
+    fn index_equal_to_length_is_out_of_bounds() {
+        let result = run("[1, 2][2]");
+        assert!(matches!(
+            result,
+            Err(RuntimeError::IndexOutOfBounds { index, len: 2 }) if index == TinyInt::from(2)
+        ));
+    }
+
+    #[test]
+    fn index_out_of_bounds_message_names_index_and_length() {
+        let message: String = runtime_error_to_message(run("[1, 2][5]").unwrap_err());
+        assert_eq!(
+            message,
+            "Index Out Of Bounds: index 5 is out of range for length 2"
+        );
+    }
+
+    #[test]
+    fn division_and_remainder_by_zero_fail() {
+        assert!(matches!(run("1 / 0"), Err(RuntimeError::DivisionByZero)));
+        assert!(matches!(run("1 % 0"), Err(RuntimeError::DivisionByZero)));
+    }
+
+    #[test]
+    fn mixed_int_and_float_arithmetic_is_float() {
+        let cases: [(&str, f64); 4] = [
+            ("1 + 2.5", 3.5),
+            ("3 / 2.0", 1.5),
+            ("2.0 * 3", 6.0),
+            ("7 % 2.0", 1.0),
+        ];
+        for (program, expected) in cases {
+            assert_eq!(
+                run(program).unwrap(),
+                Some(TinyObject::Float(expected)),
+                "{}",
+                program
+            );
+        }
+    }
+
+    #[test]
+    fn continue_skips_the_rest_of_the_body() {
+        let program: &str = "var i = 0; var sum = 0; \
+                             while i < 5 { i = i + 1; i % 2 == 0 ? continue : (); sum = sum + i }; sum";
+        assert_eq!(
+            run(program).unwrap(),
+            Some(TinyObject::Int(TinyInt::from(9)))
+        );
+    }
+
+    #[test]
+    fn break_exits_only_the_inner_loop() {
+        let program: &str = "var i = 0; var n = 0; \
+                             while i < 3 { i = i + 1; var j = 0; \
+                             while true { j = j + 1; n = n + 1; j == 2 ? break : () } }; (i, n)";
+        let expected: TinyObject = TinyObject::Tuple(Rc::new(vec![
+            TinyObject::Int(TinyInt::from(3)),
+            TinyObject::Int(TinyInt::from(6)),
+        ]));
+        assert_eq!(run(program).unwrap(), Some(expected));
+    }
+
+    fn string(s: &str) -> TinyObject {
+        TinyObject::String(Rc::from(s))
+    }
+
+    #[test]
+    fn thrown_value_is_caught() {
+        let result =
+            run("try { throw { kind: \"Oops\", message: \"bad\" } } catch e { e.message }");
+        assert_eq!(result.unwrap(), Some(string("bad")));
+    }
+
+    #[test]
+    fn vm_error_is_caught_as_a_record() {
+        let result = run("try { 1 / 0; \"\" } catch e { e.kind }");
+        assert_eq!(result.unwrap(), Some(string("DivisionByZero")));
+        let result = run("try { [1, 2][5]; \"\" } catch e { e.kind }");
+        assert_eq!(result.unwrap(), Some(string("IndexOutOfBounds")));
+    }
+
+    #[test]
+    fn throw_in_callee_unwinds_to_outer_handler() {
+        let program: &str = "fn f(n) = n == 0 ? throw { kind: \"Deep\", message: \"\" } : f(n - 1) + 1; \
+                             try { f(5); 0 } catch e { 0 - 1 }";
+        assert_eq!(
+            run(program).unwrap(),
+            Some(TinyObject::Int(TinyInt::from(-1)))
+        );
+    }
+
+    #[test]
+    fn break_and_continue_drop_the_handler_of_a_try() {
+        let program: &str = "var i = 0; var hits = 0; \
+                             while i < 5 { i = i + 1; try { i == 2 ? continue : (); \
+                             i == 4 ? break : (); hits = hits + 1 } catch e { () } }; hits";
+        assert_eq!(
+            run(program).unwrap(),
+            Some(TinyObject::Int(TinyInt::from(2)))
+        );
+        // a handler left behind would catch this throw and jump back into the loop
+        let program: &str = "var i = 0; \
+                             while i < 5 { i = i + 1; try { i < 3 ? continue : (); break } catch e { () } }; \
+                             throw { kind: \"After\", message: \"loop\" }";
+        let expected: TinyObject =
+            TinyObject::Record(Rc::new(vec![string("After"), string("loop")]));
+        assert!(matches!(run(program), Err(RuntimeError::Uncaught(error)) if error == expected));
+    }
+
+    #[test]
+    fn uncaught_throw_reaches_top_level() {
+        let result = run("throw { kind: \"Top\", message: \"level\" }");
+        let expected: TinyObject =
+            TinyObject::Record(Rc::new(vec![string("Top"), string("level")]));
+        assert!(matches!(result, Err(RuntimeError::Uncaught(error)) if error == expected));
+    }
+
+    #[test]
+    fn top_level_var_is_shared_with_closures() {
+        let result = run("var x = 1; let f = fn() => x; x = 2; f()");
+        assert_eq!(result.unwrap(), Some(TinyObject::Int(TinyInt::from(2))));
+    }
+
+    #[test]
+    fn factorial_promotes_to_big_integer() {
+        let result = run("fn fact(n) = n == 0 ? 1 : n * fact(n - 1); fact(30)");
+        let expected: TinyInt = TinyInt::parse("265252859812191058636308480000000", 10).unwrap();
+        assert_eq!(result.unwrap(), Some(TinyObject::Int(expected)));
+    }
+
+    #[test]
+    fn out_of_range_literal_follows_overflow_behavior() {
+        let program: &str = "99999999999999999999";
+        let with = |overflow: Overflow| {
+            run_with(
+                program,
+                Config {
+                    overflow,
+                    ..Config::default()
+                },
+            )
+        };
+        let int = |n: i64| Some(TinyObject::Int(TinyInt::from(n)));
+        assert_eq!(with(Overflow::Wrap).unwrap(), int(7766279631452241919));
+        assert_eq!(with(Overflow::Saturate).unwrap(), int(i64::MAX));
+        assert!(matches!(
+            with(Overflow::Trap),
+            Err(RuntimeError::LiteralOverflow(_))
+        ));
+        let min = Config {
+            overflow: Overflow::Trap,
+            ..Config::default()
+        };
+        assert_eq!(
+            run_with("-9223372036854775808", min).unwrap(),
+            int(i64::MIN)
+        );
+    }
+
+    #[test]
+    fn decimal_addition_is_exact() {
+        let result = run("0.1d + 0.2d == 0.3d");
+        assert_eq!(result.unwrap(), Some(TinyObject::Bool(true)));
+    }
+
+    #[test]
+    fn line_starting_with_minus_is_a_new_statement() {
+        let result = run("1\n-2");
+        assert_eq!(result.unwrap(), Some(TinyObject::Int(TinyInt::from(-2))));
+    }
+}